@@ -0,0 +1,248 @@
+use std::io::prelude::*;
+use std::io::{BufReader, BufWriter};
+
+use day_3::{parse_claims, Claim, Fabric};
+
+#[derive(Copy, Clone, Debug)]
+struct Crop {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+fn parse_crop(crop: &str) -> Crop {
+    let parts: Vec<usize> = crop
+        .split(',')
+        .map(|part| {
+            part.trim()
+                .parse()
+                .expect("Crop must be four comma-separated integers")
+        })
+        .collect();
+    assert_eq!(4, parts.len(), "Crop must be formatted as x,y,width,height");
+
+    Crop {
+        x: parts[0],
+        y: parts[1],
+        width: parts[2],
+        height: parts[3],
+    }
+}
+
+const OUTLINE_COLOUR: [u8; 3] = [255, 255, 255];
+
+// Unclaimed fabric is black; claimed squares run from blue (a single claim) through green and
+// yellow up to red for the most contested squares.
+fn heat_colour(count: u16, max_count: u16) -> [u8; 3] {
+    if count == 0 {
+        return [0, 0, 0];
+    }
+
+    const STOPS: [[f32; 3]; 4] = [
+        [0., 0., 255.],
+        [0., 255., 0.],
+        [255., 255., 0.],
+        [255., 0., 0.],
+    ];
+
+    let t = if max_count <= 1 {
+        0.
+    } else {
+        (count - 1) as f32 / (max_count - 1) as f32
+    };
+
+    let scaled = t * (STOPS.len() - 1) as f32;
+    let lower = (scaled.floor() as usize).min(STOPS.len() - 2);
+    let frac = scaled - lower as f32;
+
+    let mut colour = [0; 3];
+    for (c, channel) in colour.iter_mut().enumerate() {
+        let value = STOPS[lower][c] + (STOPS[lower + 1][c] - STOPS[lower][c]) * frac;
+        *channel = value.round() as u8;
+    }
+    colour
+}
+
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn put(&mut self, x: usize, y: usize, colour: [u8; 3]) {
+        let offset = (y * self.width + x) * 3;
+        self.pixels[offset..offset + 3].copy_from_slice(&colour);
+    }
+
+    fn write_ppm<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.pixels)
+    }
+
+    fn write_png<W: Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)
+    }
+}
+
+fn render(fabric: &Fabric, crop: Crop, scale: usize, outline: Option<&Claim>) -> Image {
+    let max_count = fabric.max_count();
+
+    let mut image = Image {
+        width: crop.width * scale,
+        height: crop.height * scale,
+        pixels: vec![0; crop.width * scale * crop.height * scale * 3],
+    };
+
+    for j in 0..crop.height {
+        for i in 0..crop.width {
+            let count = fabric.count_at(crop.x + i, crop.y + j);
+            let colour = heat_colour(count, max_count);
+
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    image.put(i * scale + dx, j * scale + dy, colour);
+                }
+            }
+        }
+    }
+
+    if let Some(claim) = outline {
+        // Claim edges in image pixels, relative to the crop. Anything outside the image is
+        // clipped away.
+        let rect = claim.rect;
+        let left = (rect.x as isize - crop.x as isize) * scale as isize;
+        let top = (rect.y as isize - crop.y as isize) * scale as isize;
+        let right = left + (rect.width as usize * scale) as isize - 1;
+        let bottom = top + (rect.height as usize * scale) as isize - 1;
+
+        let mut put = |x: isize, y: isize| {
+            if x >= 0 && y >= 0 && (x as usize) < image.width && (y as usize) < image.height {
+                image.put(x as usize, y as usize, OUTLINE_COLOUR);
+            }
+        };
+
+        for x in left..=right {
+            put(x, top);
+            put(x, bottom);
+        }
+        for y in top..=bottom {
+            put(left, y);
+            put(right, y);
+        }
+    }
+
+    image
+}
+
+fn main() {
+    let matches = clap::App::new("fabric-render")
+        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .required(true)
+                .help("Image file to write"),
+        )
+        .arg(
+            clap::Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["png", "ppm"])
+                .help("Image format. Defaults to the output file's extension"),
+        )
+        .arg(
+            clap::Arg::with_name("outline")
+                .long("outline")
+                .takes_value(true)
+                .value_name("ID")
+                .help("Outline the claim with this id"),
+        )
+        .arg(
+            clap::Arg::with_name("crop")
+                .long("crop")
+                .takes_value(true)
+                .value_name("X,Y,WIDTH,HEIGHT")
+                .help("Only render this region of the fabric"),
+        )
+        .arg(
+            clap::Arg::with_name("scale")
+                .long("scale")
+                .takes_value(true)
+                .default_value("1")
+                .help("Pixels per square inch of fabric"),
+        )
+        .get_matches();
+
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
+        Some(input_file) => BufReader::new(Box::new(
+            std::fs::File::open(input_file).expect("Could not open input file"),
+        )),
+        None => BufReader::new(Box::new(std::io::stdin())),
+    };
+
+    let mut input_deck = String::new();
+    reader.read_to_string(&mut input_deck).unwrap();
+
+    let claims = parse_claims(&input_deck);
+    let fabric = Fabric::from_claims(&claims);
+
+    let crop = match matches.value_of("crop") {
+        Some(crop) => parse_crop(crop),
+        None => Crop {
+            x: 0,
+            y: 0,
+            width: fabric.width(),
+            height: fabric.height(),
+        },
+    };
+
+    let scale: usize = matches
+        .value_of("scale")
+        .unwrap()
+        .parse()
+        .expect("Scale must be a positive integer");
+    assert!(scale > 0, "Scale must be a positive integer");
+
+    let outline = matches.value_of("outline").map(|id| {
+        let id: u32 = id.parse().expect("Claim id must be an integer");
+        claims
+            .iter()
+            .find(|c| c.id == id)
+            .unwrap_or_else(|| panic!("No claim with id {}", id))
+    });
+
+    if crop.width == 0 || crop.height == 0 {
+        if matches.is_present("crop") {
+            eprintln!("Nothing to render: the crop is empty");
+        } else {
+            eprintln!("Nothing to render: no claim covers any fabric");
+        }
+        std::process::exit(1);
+    }
+
+    let image = render(&fabric, crop, scale, outline);
+
+    let output = matches.value_of("output").unwrap();
+    let format = matches.value_of("format").unwrap_or_else(|| {
+        if output.to_ascii_lowercase().ends_with(".png") {
+            "png"
+        } else {
+            "ppm"
+        }
+    });
+
+    let mut writer =
+        BufWriter::new(std::fs::File::create(output).expect("Could not create output file"));
+
+    match format {
+        "png" => image.write_png(writer).expect("Could not write PNG"),
+        _ => image.write_ppm(&mut writer).expect("Could not write PPM"),
+    }
+}
@@ -0,0 +1,61 @@
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use day_3::stats::{write_claim_table, ClaimColumn};
+use day_3::{parse_claims, Fabric};
+
+fn main() {
+    let matches = clap::App::new("intersections")
+        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("per-claim")
+                .long("per-claim")
+                .help("Print overlap statistics for every claim"),
+        )
+        .arg(
+            clap::Arg::with_name("sort")
+                .long("sort")
+                .takes_value(true)
+                .value_name("COLUMN")
+                .possible_values(ClaimColumn::NAMES)
+                .default_value("id")
+                .help("Column to sort the per-claim table by"),
+        )
+        .arg(
+            clap::Arg::with_name("descending")
+                .long("descending")
+                .help("Sort the per-claim table in descending order"),
+        )
+        .get_matches();
+
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
+        Some(input_file) => BufReader::new(Box::new(
+            std::fs::File::open(input_file).expect("Could not open input file"),
+        )),
+        None => BufReader::new(Box::new(std::io::stdin())),
+    };
+
+    let mut input_deck = String::new();
+    reader.read_to_string(&mut input_deck).unwrap();
+
+    let claims = parse_claims(&input_deck);
+    let fabric = Fabric::from_claims(&claims);
+
+    if matches.is_present("per-claim") {
+        let mut stats = fabric.per_claim_stats(&claims);
+        let column: ClaimColumn = matches.value_of("sort").unwrap().parse().unwrap();
+
+        let stdout = std::io::stdout();
+        write_claim_table(
+            &mut stdout.lock(),
+            &mut stats,
+            column,
+            matches.is_present("descending"),
+        )
+        .expect("Could not write per-claim table");
+
+        return;
+    }
+
+    println!("{:?}", fabric.overlapping_squares());
+}
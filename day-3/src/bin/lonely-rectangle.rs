@@ -0,0 +1,116 @@
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use day_3::conflicts::ConflictGraph;
+use day_3::stats::{write_claim_table, ClaimColumn};
+use day_3::{parse_claims, Fabric};
+use rayon::prelude::*;
+
+fn main() {
+    let matches = clap::App::new("lonely-rectangle")
+        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("conflicts")
+                .long("conflicts")
+                .takes_value(true)
+                .value_name("FORMAT")
+                .possible_values(&["csv", "json", "dot", "summary"])
+                .help("Print the claim overlap graph instead of the lonely claim"),
+        )
+        .arg(
+            clap::Arg::with_name("top")
+                .long("top")
+                .takes_value(true)
+                .default_value("5")
+                .help("Number of claims listed by the conflict summary"),
+        )
+        .arg(
+            clap::Arg::with_name("per-claim")
+                .long("per-claim")
+                .help("Print overlap statistics for every claim"),
+        )
+        .arg(
+            clap::Arg::with_name("sort")
+                .long("sort")
+                .takes_value(true)
+                .value_name("COLUMN")
+                .possible_values(ClaimColumn::NAMES)
+                .default_value("id")
+                .help("Column to sort the per-claim table by"),
+        )
+        .arg(
+            clap::Arg::with_name("descending")
+                .long("descending")
+                .help("Sort the per-claim table in descending order"),
+        )
+        .get_matches();
+
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
+        Some(input_file) => BufReader::new(Box::new(
+            std::fs::File::open(input_file).expect("Could not open input file"),
+        )),
+        None => BufReader::new(Box::new(std::io::stdin())),
+    };
+
+    let mut input_deck = String::new();
+    reader.read_to_string(&mut input_deck).unwrap();
+
+    let claims = parse_claims(&input_deck);
+
+    if let Some(format) = matches.value_of("conflicts") {
+        let graph = ConflictGraph::from_claims(&claims).unwrap_or_else(|error| {
+            eprintln!("error: {}", error);
+            std::process::exit(1);
+        });
+
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+
+        match format {
+            "csv" => graph.write_csv(&mut stdout),
+            "json" => graph.write_json(&mut stdout),
+            "dot" => graph.write_dot(&mut stdout),
+            "summary" => {
+                let top = matches
+                    .value_of("top")
+                    .unwrap()
+                    .parse()
+                    .expect("--top must be an integer");
+                graph.write_summary(&mut stdout, top)
+            }
+            _ => unreachable!(),
+        }
+        .expect("Could not write conflict graph");
+
+        return;
+    }
+
+    if matches.is_present("per-claim") {
+        let fabric = Fabric::from_claims(&claims);
+        let mut stats = fabric.per_claim_stats(&claims);
+        let column: ClaimColumn = matches.value_of("sort").unwrap().parse().unwrap();
+
+        let stdout = std::io::stdout();
+        write_claim_table(
+            &mut stdout.lock(),
+            &mut stats,
+            column,
+            matches.is_present("descending"),
+        )
+        .expect("Could not write per-claim table");
+
+        return;
+    }
+
+    let claim_idx = (0..claims.len())
+        .into_par_iter()
+        .find_any(|i| {
+            !(0..claims.len())
+                .into_par_iter()
+                .filter(|j| j != i)
+                .any(|j| claims[*i].rect.intersects(&claims[j].rect))
+        })
+        .expect("Could not find a claim that doesn't overlap with any other claim");
+
+    println!("{}", claims[claim_idx].id);
+}
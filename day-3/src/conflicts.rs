@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::Claim;
+
+/// Two claims that share at least one square inch of fabric. `a` is always the smaller id.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Conflict {
+    pub a: u32,
+    pub b: u32,
+    pub shared_area: u64,
+}
+
+/// The overlap graph between claims: one node per claim, one edge per pair of overlapping claims
+/// weighted by the area they share.
+#[derive(Clone, Debug, Serialize)]
+pub struct ConflictGraph {
+    pub claims: Vec<u32>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Ids given to more than one claim, which the graph has no way to tell apart.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateIds {
+    pub ids: Vec<u32>,
+}
+
+impl fmt::Display for DuplicateIds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "claim ids used more than once:")?;
+        for id in &self.ids {
+            write!(f, " #{}", id)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DuplicateIds {}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClaimConflicts {
+    pub id: u32,
+    pub conflicts: usize,
+    pub total_overlap: u64,
+}
+
+impl ConflictGraph {
+    /// Fails if any two claims share an id, as their edges could not be told apart.
+    pub fn from_claims(claims: &[Claim]) -> Result<Self, DuplicateIds> {
+        let mut ids: Vec<_> = claims.iter().map(|c| c.id).collect();
+        ids.sort();
+
+        let mut duplicates: Vec<_> = ids
+            .windows(2)
+            .filter(|pair| pair[0] == pair[1])
+            .map(|pair| pair[0])
+            .collect();
+        duplicates.dedup();
+        if !duplicates.is_empty() {
+            return Err(DuplicateIds { ids: duplicates });
+        }
+
+        let mut conflicts: Vec<_> = (0..claims.len())
+            .into_par_iter()
+            .flat_map(|i| {
+                (i + 1..claims.len()).into_par_iter().filter_map(move |j| {
+                    let shared = claims[i].rect.intersection(&claims[j].rect)?;
+                    let (a, b) = if claims[i].id < claims[j].id {
+                        (claims[i].id, claims[j].id)
+                    } else {
+                        (claims[j].id, claims[i].id)
+                    };
+
+                    Some(Conflict {
+                        a,
+                        b,
+                        shared_area: shared.area(),
+                    })
+                })
+            })
+            .collect();
+
+        conflicts.par_sort_by_key(|c| (c.a, c.b));
+
+        Ok(ConflictGraph {
+            claims: ids,
+            conflicts,
+        })
+    }
+
+    /// Conflict counts and total shared area for every claim, ordered by claim id.
+    pub fn claim_conflicts(&self) -> Vec<ClaimConflicts> {
+        let index = self.index();
+
+        let mut stats: Vec<_> = self
+            .claims
+            .iter()
+            .map(|&id| ClaimConflicts {
+                id,
+                ..Default::default()
+            })
+            .collect();
+
+        for conflict in &self.conflicts {
+            for id in &[conflict.a, conflict.b] {
+                let stat = &mut stats[index[id]];
+                stat.conflicts += 1;
+                stat.total_overlap += conflict.shared_area;
+            }
+        }
+
+        stats
+    }
+
+    /// The connected components of the graph, largest first. Claims within a component are
+    /// ordered by id, and components of equal size are ordered by their smallest id.
+    pub fn components(&self) -> Vec<Vec<u32>> {
+        let index = self.index();
+
+        let mut parents: Vec<_> = (0..self.claims.len()).collect();
+
+        fn find(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+
+        for conflict in &self.conflicts {
+            let a = find(&mut parents, index[&conflict.a]);
+            let b = find(&mut parents, index[&conflict.b]);
+            parents[a.max(b)] = a.min(b);
+        }
+
+        let mut components: HashMap<usize, Vec<u32>> = HashMap::new();
+        for (i, &id) in self.claims.iter().enumerate() {
+            let root = find(&mut parents, i);
+            components.entry(root).or_default().push(id);
+        }
+
+        let mut components: Vec<_> = components.into_values().collect();
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+        components
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "claim_a,claim_b,shared_area")?;
+        for conflict in &self.conflicts {
+            writeln!(
+                writer,
+                "{},{},{}",
+                conflict.a, conflict.b, conflict.shared_area
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *writer, self)?;
+        writeln!(writer)
+    }
+
+    pub fn write_dot<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "graph conflicts {{")?;
+        for id in &self.claims {
+            writeln!(writer, "    {};", id)?;
+        }
+        for conflict in &self.conflicts {
+            writeln!(
+                writer,
+                "    {} -- {} [weight={}, label=\"{}\"];",
+                conflict.a, conflict.b, conflict.shared_area, conflict.shared_area
+            )?;
+        }
+        writeln!(writer, "}}")
+    }
+
+    /// Human-readable statistics: component structure, the claim with the most conflicts, and
+    /// the `top` claims with the largest total overlap.
+    pub fn write_summary<W: Write>(&self, writer: &mut W, top: usize) -> io::Result<()> {
+        let components = self.components();
+        let stats = self.claim_conflicts();
+
+        writeln!(writer, "claims: {}", self.claims.len())?;
+        writeln!(writer, "conflicts: {}", self.conflicts.len())?;
+        writeln!(
+            writer,
+            "components: {} (largest: {} claims)",
+            components.len(),
+            components.first().map_or(0, |c| c.len())
+        )?;
+        writeln!(
+            writer,
+            "uncontested claims: {}",
+            stats.iter().filter(|s| s.conflicts == 0).count()
+        )?;
+
+        // Ties go to the smallest id
+        if let Some(most_contested) = stats
+            .iter()
+            .filter(|s| s.conflicts > 0)
+            .max_by(|a, b| a.conflicts.cmp(&b.conflicts).then(b.id.cmp(&a.id)))
+        {
+            writeln!(
+                writer,
+                "most contested: #{} ({} conflicts)",
+                most_contested.id, most_contested.conflicts
+            )?;
+        }
+
+        let mut by_overlap: Vec<_> = stats.iter().filter(|s| s.total_overlap > 0).collect();
+        by_overlap.sort_by(|a, b| b.total_overlap.cmp(&a.total_overlap).then(a.id.cmp(&b.id)));
+
+        writeln!(writer, "largest total overlap:")?;
+        for stat in by_overlap.iter().take(top) {
+            writeln!(
+                writer,
+                "  #{}: {} square inches over {} conflicts",
+                stat.id, stat.total_overlap, stat.conflicts
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn index(&self) -> HashMap<u32, usize> {
+        self.claims
+            .iter()
+            .enumerate()
+            .map(|(i, &id)| (id, i))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_claims;
+
+    #[test]
+    fn duplicate_ids_are_rejected() {
+        let claims = parse_claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#2 @ 5,5: 2x2\n");
+        assert_eq!(
+            ConflictGraph::from_claims(&claims).unwrap_err(),
+            DuplicateIds { ids: vec![2] }
+        );
+    }
+
+    #[test]
+    fn overlapping_claims_share_an_edge() {
+        let claims = parse_claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n");
+        let graph = ConflictGraph::from_claims(&claims).unwrap();
+        assert_eq!(
+            graph.conflicts,
+            vec![Conflict {
+                a: 1,
+                b: 2,
+                shared_area: 4
+            }]
+        );
+        assert_eq!(graph.components(), vec![vec![1, 2], vec![3]]);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fmt;
+use std::str::FromStr;
+
+use ndarray::prelude::*;
+use ndarray::s;
+use rayon::prelude::*;
+
+pub mod conflicts;
+pub mod stats;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+
+        Some(Rect {
+            x,
+            y,
+            width: self.right().min(other.right()) - x,
+            height: self.bottom().min(other.bottom()) - y,
+        })
+    }
+
+    /// The smallest rectangle containing both `self` and `other`.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+
+        Rect {
+            x,
+            y,
+            width: self.right().max(other.right()) - x,
+            height: self.bottom().max(other.bottom()) - y,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Claim {
+    pub id: u32,
+    pub rect: Rect,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseClaimError {
+    Expected {
+        token: &'static str,
+        at: usize,
+    },
+    InvalidNumber {
+        at: usize,
+    },
+    TrailingInput {
+        at: usize,
+    },
+    /// The claim at `at` reaches past the largest coordinate a fabric can have.
+    OutOfRange {
+        at: usize,
+    },
+}
+
+impl fmt::Display for ParseClaimError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseClaimError::Expected { token, at } => {
+                write!(f, "expected {} at column {}", token, at + 1)
+            }
+            ParseClaimError::InvalidNumber { at } => {
+                write!(f, "invalid number at column {}", at + 1)
+            }
+            ParseClaimError::TrailingInput { at } => {
+                write!(f, "unexpected input at column {}", at + 1)
+            }
+            ParseClaimError::OutOfRange { at } => {
+                write!(f, "claim at column {} is too large for the fabric", at + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseClaimError {}
+
+struct ClaimTokenizer<'a> {
+    line: &'a [u8],
+    next_index: usize,
+}
+
+impl<'a> ClaimTokenizer<'a> {
+    fn skip_whitespace(&mut self) {
+        while self.next_index < self.line.len() && self.line[self.next_index].is_ascii_whitespace()
+        {
+            self.next_index += 1;
+        }
+    }
+
+    fn expect(&mut self, token: &'static str) -> Result<(), ParseClaimError> {
+        self.skip_whitespace();
+
+        let at = self.next_index;
+        if self.line[at..].starts_with(token.as_bytes()) {
+            self.next_index += token.len();
+            Ok(())
+        } else {
+            Err(ParseClaimError::Expected { token, at })
+        }
+    }
+
+    /// The column the next token starts at.
+    fn position(&mut self) -> usize {
+        self.skip_whitespace();
+        self.next_index
+    }
+
+    fn read_number(&mut self) -> Result<u32, ParseClaimError> {
+        self.skip_whitespace();
+
+        let at = self.next_index;
+        let len = self.line[at..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+
+        if len == 0 {
+            return Err(ParseClaimError::Expected {
+                token: "a number",
+                at,
+            });
+        }
+
+        self.next_index += len;
+
+        // Only ASCII digits were consumed, so this can only fail on overflow
+        std::str::from_utf8(&self.line[at..self.next_index])
+            .unwrap()
+            .parse()
+            .map_err(|_| ParseClaimError::InvalidNumber { at })
+    }
+
+    fn finish(&mut self) -> Result<(), ParseClaimError> {
+        self.skip_whitespace();
+
+        if self.next_index == self.line.len() {
+            Ok(())
+        } else {
+            Err(ParseClaimError::TrailingInput {
+                at: self.next_index,
+            })
+        }
+    }
+}
+
+impl FromStr for Claim {
+    type Err = ParseClaimError;
+
+    /// Parses claims of the form `#123 @ 3,2: 5x4`. Any amount of whitespace (including none) is
+    /// accepted between the tokens.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut tokens = ClaimTokenizer {
+            line: line.as_bytes(),
+            next_index: 0,
+        };
+
+        tokens.expect("#")?;
+        let id = tokens.read_number()?;
+        tokens.expect("@")?;
+        let at = tokens.position();
+        let x = tokens.read_number()?;
+        tokens.expect(",")?;
+        let y = tokens.read_number()?;
+        tokens.expect(":")?;
+        let width = tokens.read_number()?;
+        tokens.expect("x")?;
+        let height = tokens.read_number()?;
+        tokens.finish()?;
+
+        // Keep `right` and `bottom` from overflowing.
+        if x.checked_add(width).is_none() || y.checked_add(height).is_none() {
+            return Err(ParseClaimError::OutOfRange { at });
+        }
+
+        Ok(Claim {
+            id,
+            rect: Rect {
+                x,
+                y,
+                width,
+                height,
+            },
+        })
+    }
+}
+
+/// Parses one claim per line, skipping blank lines.
+pub fn parse_claims(input: &str) -> Vec<Claim> {
+    input
+        .par_lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.parse()
+                .unwrap_or_else(|e| panic!("Invalid claim \"{}\": {}", line, e))
+        })
+        .collect()
+}
+
+/// The number of claims covering each square inch of fabric, indexed by `[x, y]`.
+pub struct Fabric {
+    pub counts: Array2<u16>,
+}
+
+impl Fabric {
+    pub fn from_claims(claims: &[Claim]) -> Self {
+        let dims = [
+            claims.iter().map(|c| c.rect.right()).max().unwrap_or(0) as usize,
+            claims.iter().map(|c| c.rect.bottom()).max().unwrap_or(0) as usize,
+        ];
+
+        let mut counts = Array2::<u16>::zeros(dims);
+
+        for claim in claims {
+            counts
+                .slice_mut(s![
+                    claim.rect.x as usize..claim.rect.right() as usize,
+                    claim.rect.y as usize..claim.rect.bottom() as usize
+                ])
+                .map_inplace(|count| *count += 1);
+        }
+
+        Fabric { counts }
+    }
+
+    pub fn width(&self) -> usize {
+        self.counts.rows()
+    }
+
+    pub fn height(&self) -> usize {
+        self.counts.cols()
+    }
+
+    pub fn count_at(&self, x: usize, y: usize) -> u16 {
+        self.counts.get([x, y]).cloned().unwrap_or(0)
+    }
+
+    pub fn max_count(&self) -> u16 {
+        self.counts.iter().cloned().max().unwrap_or(0)
+    }
+
+    /// The number of square inches covered by two or more claims.
+    pub fn overlapping_squares(&self) -> usize {
+        self.counts.iter().filter(|x| **x >= 2).count()
+    }
+}
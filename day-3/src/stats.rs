@@ -0,0 +1,121 @@
+use std::cmp::Ordering;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use ndarray::s;
+use rayon::prelude::*;
+
+use crate::{Claim, Fabric};
+
+/// How much of a claim is shared with other claims.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClaimStats {
+    pub id: u32,
+    pub area: u64,
+    /// Square inches of the claim covered by at least one other claim.
+    pub contested: u64,
+    /// The most claims stacked on any single square inch of this claim, itself included.
+    pub max_depth: u16,
+    /// Fraction of the claim's area covered by no other claim. Empty claims count as entirely
+    /// exclusive.
+    pub exclusive: f64,
+}
+
+impl Fabric {
+    pub fn claim_stats(&self, claim: &Claim) -> ClaimStats {
+        let rect = claim.rect;
+        let counts = self.counts.slice(s![
+            rect.x as usize..rect.right() as usize,
+            rect.y as usize..rect.bottom() as usize
+        ]);
+
+        let contested = counts.iter().filter(|c| **c >= 2).count() as u64;
+        let max_depth = counts.iter().cloned().max().unwrap_or(0);
+        let area = rect.area();
+
+        ClaimStats {
+            id: claim.id,
+            area,
+            contested,
+            max_depth,
+            exclusive: if area == 0 {
+                1.
+            } else {
+                (area - contested) as f64 / area as f64
+            },
+        }
+    }
+
+    pub fn per_claim_stats(&self, claims: &[Claim]) -> Vec<ClaimStats> {
+        claims.par_iter().map(|c| self.claim_stats(c)).collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClaimColumn {
+    Id,
+    Area,
+    Contested,
+    MaxDepth,
+    Exclusive,
+}
+
+impl ClaimColumn {
+    pub const NAMES: &'static [&'static str] =
+        &["id", "area", "contested", "max-depth", "exclusive"];
+
+    fn compare(self, a: &ClaimStats, b: &ClaimStats) -> Ordering {
+        match self {
+            ClaimColumn::Id => a.id.cmp(&b.id),
+            ClaimColumn::Area => a.area.cmp(&b.area),
+            ClaimColumn::Contested => a.contested.cmp(&b.contested),
+            ClaimColumn::MaxDepth => a.max_depth.cmp(&b.max_depth),
+            ClaimColumn::Exclusive => a.exclusive.partial_cmp(&b.exclusive).unwrap(),
+        }
+    }
+}
+
+impl FromStr for ClaimColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "id" => Ok(ClaimColumn::Id),
+            "area" => Ok(ClaimColumn::Area),
+            "contested" => Ok(ClaimColumn::Contested),
+            "max-depth" => Ok(ClaimColumn::MaxDepth),
+            "exclusive" => Ok(ClaimColumn::Exclusive),
+            _ => Err(format!("Unknown column: {}", s)),
+        }
+    }
+}
+
+/// Sorts `stats` by `column` and writes them as an aligned table. Rows that compare equal are
+/// ordered by claim id.
+pub fn write_claim_table<W: Write>(
+    writer: &mut W,
+    stats: &mut [ClaimStats],
+    column: ClaimColumn,
+    descending: bool,
+) -> io::Result<()> {
+    stats.par_sort_by(|a, b| {
+        let order = column.compare(a, b);
+        let order = if descending { order.reverse() } else { order };
+        order.then(a.id.cmp(&b.id))
+    });
+
+    writeln!(
+        writer,
+        "{:>8} {:>8} {:>10} {:>10} {:>10}",
+        "id", "area", "contested", "max-depth", "exclusive"
+    )?;
+    for stat in stats.iter() {
+        writeln!(
+            writer,
+            "{:>8} {:>8} {:>10} {:>10} {:>10.3}",
+            stat.id, stat.area, stat.contested, stat.max_depth, stat.exclusive
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use day_4::ingest::EventSource;
+use day_4::load_guard_log;
+use day_4::strategy::{write_ranking, Strategy};
+
+fn main() {
+    let matches = clap::App::new("consistent-guard")
+        .arg(
+            clap::Arg::with_name("input")
+                .required(false)
+                .multiple(true)
+                .help("Guard logs to merge; reads stdin if none are given"),
+        )
+        .arg(
+            clap::Arg::with_name("chunk-size")
+                .long("chunk-size")
+                .takes_value(true)
+                .value_name("LINES")
+                .default_value("1000000")
+                .help("Sort at most LINES events in memory at once, spilling the rest to disk"),
+        )
+        .arg(
+            clap::Arg::with_name("validate")
+                .long("validate")
+                .help("List every inconsistency in the guard log and exit"),
+        )
+        .arg(
+            clap::Arg::with_name("repair")
+                .long("repair")
+                .help("Repair inconsistencies in the guard log instead of aborting"),
+        )
+        .arg(
+            clap::Arg::with_name("strategy")
+                .long("strategy")
+                .takes_value(true)
+                .possible_values(&Strategy::names())
+                .default_value("most-frequent-minute")
+                .help("How to pick the guard"),
+        )
+        .arg(
+            clap::Arg::with_name("top")
+                .long("top")
+                .takes_value(true)
+                .value_name("N")
+                .help("Print the N best guards with their statistics instead of the answer"),
+        )
+        .get_matches();
+
+    let sources = match matches.values_of("input") {
+        Some(paths) => paths.map(EventSource::open).collect(),
+        None => vec![EventSource::stdin()],
+    };
+    let chunk_size: usize = matches
+        .value_of("chunk-size")
+        .unwrap()
+        .parse()
+        .expect("--chunk-size must be an integer");
+
+    let log = load_guard_log(
+        sources,
+        chunk_size,
+        matches.is_present("validate"),
+        matches.is_present("repair"),
+    );
+    let strategy = Strategy::by_name(matches.value_of("strategy").unwrap()).unwrap();
+
+    if let Some(top) = matches.value_of("top") {
+        let top: usize = top.parse().expect("--top must be an integer");
+        let ranking = strategy.rank(&log);
+
+        let stdout = std::io::stdout();
+        write_ranking(&mut stdout.lock(), &ranking[..top.min(ranking.len())])
+            .expect("Could not write ranking");
+        return;
+    }
+
+    println!("{}", strategy.answer(&log));
+}
@@ -0,0 +1,82 @@
+use day_4::ingest::EventSource;
+use day_4::load_guard_log;
+use day_4::report::{night_rows, write_guards_csv, write_json, write_nights_csv, write_text};
+use day_4::strategy::guard_stats;
+
+fn main() {
+    let matches = clap::App::new("guard-timeline")
+        .arg(
+            clap::Arg::with_name("input")
+                .required(false)
+                .multiple(true)
+                .help("Guard logs to merge; reads stdin if none are given"),
+        )
+        .arg(
+            clap::Arg::with_name("chunk-size")
+                .long("chunk-size")
+                .takes_value(true)
+                .value_name("LINES")
+                .default_value("1000000")
+                .help("Sort at most LINES events in memory at once, spilling the rest to disk"),
+        )
+        .arg(
+            clap::Arg::with_name("validate")
+                .long("validate")
+                .help("List every inconsistency in the guard log and exit"),
+        )
+        .arg(
+            clap::Arg::with_name("repair")
+                .long("repair")
+                .help("Repair inconsistencies in the guard log instead of aborting"),
+        )
+        .arg(
+            clap::Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["text", "csv", "json"])
+                .default_value("text"),
+        )
+        .arg(
+            clap::Arg::with_name("table")
+                .long("table")
+                .takes_value(true)
+                .possible_values(&["nights", "guards"])
+                .default_value("nights")
+                .help("Which table to export as CSV"),
+        )
+        .get_matches();
+
+    let sources = match matches.values_of("input") {
+        Some(paths) => paths.map(EventSource::open).collect(),
+        None => vec![EventSource::stdin()],
+    };
+    let chunk_size: usize = matches
+        .value_of("chunk-size")
+        .unwrap()
+        .parse()
+        .expect("--chunk-size must be an integer");
+
+    let log = load_guard_log(
+        sources,
+        chunk_size,
+        matches.is_present("validate"),
+        matches.is_present("repair"),
+    );
+
+    let nights = night_rows(&log);
+    let guards = guard_stats(&log);
+
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+
+    match (
+        matches.value_of("format").unwrap(),
+        matches.value_of("table").unwrap(),
+    ) {
+        ("csv", "guards") => write_guards_csv(&mut stdout, &guards),
+        ("csv", _) => write_nights_csv(&mut stdout, &nights),
+        ("json", _) => write_json(&mut stdout, &nights, &guards),
+        _ => write_text(&mut stdout, &nights, &guards),
+    }
+    .expect("Could not write timeline");
+}
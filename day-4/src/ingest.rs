@@ -0,0 +1,276 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{prelude::*, BufReader, BufWriter};
+
+use chrono::NaiveDateTime;
+use rayon::iter::Either;
+use rayon::prelude::*;
+use tempfile::{NamedTempFile, TempPath};
+
+use crate::{Event, EventParser, EventType, Location, LogIssue};
+
+/// A guard log to read events from.
+pub struct EventSource {
+    pub name: String,
+    pub reader: Box<dyn BufRead>,
+}
+
+impl EventSource {
+    pub fn open(path: &str) -> Self {
+        EventSource {
+            name: path.to_owned(),
+            reader: Box::new(BufReader::new(
+                File::open(path).expect("Could not open input file"),
+            )),
+        }
+    }
+
+    pub fn stdin() -> Self {
+        EventSource {
+            name: "<stdin>".to_owned(),
+            reader: Box::new(BufReader::new(std::io::stdin())),
+        }
+    }
+}
+
+const SPILL_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+fn write_spilled<W: Write>(writer: &mut W, event: &Event) -> std::io::Result<()> {
+    write!(
+        writer,
+        "{} {} {} ",
+        event.time.format(SPILL_TIME_FORMAT),
+        event.location.source,
+        event.location.line
+    )?;
+    match event.event_type {
+        EventType::BeginShift { guard_id } => writeln!(writer, "B{}", guard_id),
+        EventType::FallsAsleep => writeln!(writer, "S"),
+        EventType::WakesUp => writeln!(writer, "W"),
+    }
+}
+
+fn read_spilled(line: &str) -> Event {
+    let mut fields = line.split(' ');
+    let mut next = || fields.next().expect("Spilled event was truncated");
+
+    let time = NaiveDateTime::parse_from_str(next(), SPILL_TIME_FORMAT).unwrap();
+    let source = next().parse().unwrap();
+    let line = next().parse().unwrap();
+    let event_type = match next() {
+        "S" => EventType::FallsAsleep,
+        "W" => EventType::WakesUp,
+        kind => EventType::BeginShift {
+            guard_id: kind[1..].parse().unwrap(),
+        },
+    };
+
+    Event {
+        time,
+        event_type,
+        location: Location { source, line },
+    }
+}
+
+/// How many runs are merged at once. With more runs than this, groups of them are merged into
+/// longer runs first, so that no more than this many spill files are ever open at once.
+const MERGE_FAN_IN: usize = 64;
+
+/// A sorted run of events, either still in memory or spilled to a temporary file that is closed
+/// until the run is merged.
+enum Run {
+    Memory(Vec<Event>),
+    Spilled(TempPath),
+}
+
+impl Run {
+    fn open(self) -> RunReader {
+        match self {
+            Run::Memory(events) => RunReader::Memory(events.into_iter()),
+            Run::Spilled(path) => RunReader::Spilled {
+                lines: BufReader::new(File::open(&path).expect("Could not open spilled events"))
+                    .lines(),
+                _path: path,
+            },
+        }
+    }
+}
+
+enum RunReader {
+    Memory(std::vec::IntoIter<Event>),
+    Spilled {
+        lines: std::io::Lines<BufReader<File>>,
+        // Deletes the file once the run has been read
+        _path: TempPath,
+    },
+}
+
+impl Iterator for RunReader {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        match self {
+            RunReader::Memory(events) => events.next(),
+            RunReader::Spilled { lines, .. } => lines
+                .next()
+                .map(|line| read_spilled(&line.expect("Could not read spilled events"))),
+        }
+    }
+}
+
+struct Head {
+    event: Event,
+    run: usize,
+}
+
+impl Head {
+    fn key(&self) -> (NaiveDateTime, Location) {
+        (self.event.time, self.event.location)
+    }
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Head {}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Events from every source, merged into a single chronological stream. Events with the same
+/// timestamp are ordered by source, then by line.
+pub struct SortedEvents {
+    /// Every line that could not be parsed, and so is missing from the stream.
+    pub issues: Vec<LogIssue>,
+    runs: Vec<RunReader>,
+    heads: BinaryHeap<Reverse<Head>>,
+}
+
+impl Iterator for SortedEvents {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        let Reverse(head) = self.heads.pop()?;
+
+        if let Some(event) = self.runs[head.run].next() {
+            self.heads.push(Reverse(Head {
+                event,
+                run: head.run,
+            }));
+        }
+
+        Some(head.event)
+    }
+}
+
+fn sort_chunk(
+    parser: &EventParser,
+    chunk: &mut Vec<(Location, String)>,
+    issues: &mut Vec<LogIssue>,
+) -> Vec<Event> {
+    let (mut events, chunk_issues): (Vec<_>, Vec<_>) = chunk
+        .par_iter()
+        .map(|(location, line)| parser.parse_event(*location, line))
+        .partition_map(|parsed| match parsed {
+            Ok(event) => Either::Left(event),
+            Err(issue) => Either::Right(issue),
+        });
+    chunk.clear();
+    issues.extend(chunk_issues);
+
+    events.par_sort_by_key(|e| (e.time, e.location));
+    events
+}
+
+fn spill<I: IntoIterator<Item = Event>>(events: I) -> Run {
+    let file = NamedTempFile::new().expect("Could not create temporary file");
+
+    let mut writer = BufWriter::new(file);
+    for event in events {
+        write_spilled(&mut writer, &event).expect("Could not spill events");
+    }
+
+    let file = writer.into_inner().expect("Could not spill events");
+    Run::Spilled(file.into_temp_path())
+}
+
+/// Merges at most `MERGE_FAN_IN` runs into a single chronological stream.
+fn merge(runs: Vec<Run>, issues: Vec<LogIssue>) -> SortedEvents {
+    assert!(runs.len() <= MERGE_FAN_IN);
+
+    let mut runs: Vec<_> = runs.into_iter().map(Run::open).collect();
+    let heads = runs
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(run, events)| events.next().map(|event| Reverse(Head { event, run })))
+        .collect();
+
+    SortedEvents {
+        issues,
+        runs,
+        heads,
+    }
+}
+
+/// Reads every source and sorts their events with an external merge sort: at most `chunk_size`
+/// lines are held in memory at once, and each full chunk is sorted and spilled to a temporary
+/// file before the runs are merged, in several passes if there are too many to merge at once.
+/// Blank lines are skipped, as are lines that are not valid events, which are recorded in
+/// `issues` instead.
+pub fn sorted_events(sources: Vec<EventSource>, chunk_size: usize) -> SortedEvents {
+    assert!(chunk_size > 0, "Chunk size must be positive");
+
+    let parser = EventParser::new();
+
+    let mut runs = vec![];
+    let mut issues = vec![];
+    let mut chunk = Vec::with_capacity(chunk_size.min(1 << 20));
+
+    for (source, event_source) in sources.into_iter().enumerate() {
+        for (i, line) in event_source.reader.lines().enumerate() {
+            let line = line.expect("Could not read input");
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            chunk.push((
+                Location {
+                    source,
+                    line: i + 1,
+                },
+                line,
+            ));
+
+            if chunk.len() == chunk_size {
+                runs.push(spill(sort_chunk(&parser, &mut chunk, &mut issues)));
+            }
+        }
+    }
+
+    runs.push(Run::Memory(sort_chunk(&parser, &mut chunk, &mut issues)));
+
+    while runs.len() > MERGE_FAN_IN {
+        let mut merged = vec![];
+        let mut remaining = runs.into_iter().peekable();
+        while remaining.peek().is_some() {
+            let group: Vec<_> = remaining.by_ref().take(MERGE_FAN_IN).collect();
+            merged.push(spill(merge(group, vec![])));
+        }
+        runs = merged;
+    }
+
+    merge(runs, issues)
+}
@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use regex::Regex;
+
+pub mod ingest;
+pub mod report;
+pub mod strategy;
+
+use crate::ingest::{sorted_events, EventSource};
+
+#[derive(Copy, Clone, Debug)]
+pub enum EventType {
+    BeginShift { guard_id: u32 },
+    FallsAsleep,
+    WakesUp,
+}
+
+/// Where an event was read from: the index of its source, and its 1-based line in that source.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    pub source: usize,
+    pub line: usize,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Event {
+    pub time: NaiveDateTime,
+    pub event_type: EventType,
+    pub location: Location,
+}
+
+pub struct EventParser {
+    event_regex: Regex,
+    message_regex: Regex,
+}
+
+impl EventParser {
+    pub fn new() -> Self {
+        Self {
+            event_regex: Regex::new(r"^\[(\d{4})-(\d{2})-(\d{2}) (\d{2}):(\d{2})\] (.*)$").unwrap(),
+            message_regex: Regex::new(r"^Guard #(\d+) begins shift$").unwrap(),
+        }
+    }
+
+    /// Parses a single line, or describes why it is not a valid event.
+    pub fn parse_event(&self, location: Location, line: &str) -> Result<Event, LogIssue> {
+        let issue = |kind| LogIssue { location, kind };
+
+        let captures = self
+            .event_regex
+            .captures(line)
+            .ok_or_else(|| issue(IssueKind::MalformedLine))?;
+
+        // The regex only matches digits, and too few of them to overflow
+        let field = |i: usize| captures[i].parse().unwrap();
+        let time = NaiveDate::from_ymd_opt(field(1) as i32, field(2), field(3))
+            .and_then(|date| date.and_hms_opt(field(4), field(5), 0))
+            .ok_or_else(|| issue(IssueKind::InvalidTimestamp))?;
+
+        let message = &captures[6];
+        let event_type = match message {
+            "falls asleep" => EventType::FallsAsleep,
+            "wakes up" => EventType::WakesUp,
+            _ => {
+                let guard_id = self
+                    .message_regex
+                    .captures(message)
+                    .and_then(|captures| captures[1].parse().ok())
+                    .ok_or_else(|| issue(IssueKind::InvalidMessage))?;
+                EventType::BeginShift { guard_id }
+            }
+        };
+
+        Ok(Event {
+            time,
+            event_type,
+            location,
+        })
+    }
+}
+
+impl Default for EventParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub const MINUTES_PER_DAY: usize = 24 * 60;
+
+/// One guard's shift, and the naps they took during it.
+#[derive(Clone, Debug)]
+pub struct Shift {
+    pub guard_id: u32,
+    pub begins: NaiveDateTime,
+    pub naps: Vec<Range<NaiveDateTime>>,
+}
+
+impl Shift {
+    /// The date of the midnight hour the shift covers. Shifts that begin in the evening cover the
+    /// following night.
+    pub fn night(&self) -> NaiveDate {
+        if self.begins.hour() >= 12 {
+            self.begins.date().succ_opt().unwrap()
+        } else {
+            self.begins.date()
+        }
+    }
+}
+
+/// The intervals each guard spent asleep, both per shift and across all of their shifts. A nap
+/// may run past the end of the hour, or past midnight.
+pub struct GuardLog {
+    pub shifts: Vec<Shift>,
+    pub guards: HashMap<u32, Vec<Range<NaiveDateTime>>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The line is not a timestamp followed by a message.
+    MalformedLine,
+    /// The timestamp is not a real date and time.
+    InvalidTimestamp,
+    /// The message is not one of the three kinds of event.
+    InvalidMessage,
+    /// A guard fell asleep while already asleep since `since`.
+    DoubleSleep {
+        since: Location,
+    },
+    WakeWithoutSleep,
+    DuplicateTimestamp {
+        other: Location,
+    },
+    BeforeFirstShift,
+    /// A shift began while the previous guard was still asleep since `since`.
+    ShiftWhileAsleep {
+        since: Location,
+    },
+    /// The log ended while the last guard was asleep.
+    NeverWakes,
+}
+
+/// An inconsistency in a guard log, found at `location`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LogIssue {
+    pub location: Location,
+    pub kind: IssueKind,
+}
+
+impl LogIssue {
+    /// Displays the issue, naming locations after `sources` when there is more than one.
+    pub fn display<'a>(&'a self, sources: &'a [String]) -> impl fmt::Display + 'a {
+        IssueDisplay {
+            issue: self,
+            sources,
+        }
+    }
+}
+
+struct IssueDisplay<'a> {
+    issue: &'a LogIssue,
+    sources: &'a [String],
+}
+
+impl<'a> IssueDisplay<'a> {
+    fn location(&self, location: Location) -> String {
+        if self.sources.len() > 1 {
+            format!("{}:{}", self.sources[location.source], location.line)
+        } else {
+            format!("line {}", location.line)
+        }
+    }
+}
+
+impl<'a> fmt::Display for IssueDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.location(self.issue.location))?;
+        match self.issue.kind {
+            IssueKind::MalformedLine => {
+                write!(f, "expected a line like \"[1518-11-01 00:00] wakes up\"")
+            }
+            IssueKind::InvalidTimestamp => write!(f, "event has an invalid timestamp"),
+            IssueKind::InvalidMessage => write!(f, "event has an unrecognised message"),
+            IssueKind::DoubleSleep { since } => write!(
+                f,
+                "guard falls asleep while already asleep since {}",
+                self.location(since)
+            ),
+            IssueKind::WakeWithoutSleep => write!(f, "guard wakes up without falling asleep"),
+            IssueKind::DuplicateTimestamp { other } => write!(
+                f,
+                "event has the same timestamp as {}",
+                self.location(other)
+            ),
+            IssueKind::BeforeFirstShift => write!(f, "event occurs before the first shift"),
+            IssueKind::ShiftWhileAsleep { since } => write!(
+                f,
+                "shift begins while the previous guard is asleep since {}",
+                self.location(since)
+            ),
+            IssueKind::NeverWakes => write!(f, "guard falls asleep and never wakes up"),
+        }
+    }
+}
+
+// Dangling naps are closed at the end of the hour they started in, but never after the event
+// that interrupted them.
+fn end_of_shift_hour(start: NaiveDateTime, limit: Option<NaiveDateTime>) -> NaiveDateTime {
+    let end = start.with_minute(0).unwrap() + Duration::hours(1);
+    match limit {
+        Some(limit) if limit < end => limit,
+        _ => end,
+    }
+}
+
+impl GuardLog {
+    /// Assembles sorted events into per-guard sleep intervals, along with every inconsistency
+    /// found in them. Inconsistent events are repaired where possible: dangling naps are closed
+    /// at the end of the hour they started in, and events that cannot be attributed to a nap or
+    /// a guard are skipped.
+    pub fn assemble<I: IntoIterator<Item = Event>>(events: I) -> (Self, Vec<LogIssue>) {
+        let mut shifts: Vec<Shift> = vec![];
+        let mut issues = vec![];
+
+        let mut current_shift: Option<Shift> = None;
+        let mut sleep_start: Option<Event> = None;
+        let mut previous: Option<Event> = None;
+        for event in events {
+            if let Some(previous) = previous.filter(|p| p.time == event.time) {
+                issues.push(LogIssue {
+                    location: event.location,
+                    kind: IssueKind::DuplicateTimestamp {
+                        other: previous.location,
+                    },
+                });
+            }
+            previous = Some(event);
+
+            match (event.event_type, current_shift.as_mut()) {
+                (EventType::BeginShift { guard_id }, _) => {
+                    if let Some(start) = sleep_start.take() {
+                        issues.push(LogIssue {
+                            location: event.location,
+                            kind: IssueKind::ShiftWhileAsleep {
+                                since: start.location,
+                            },
+                        });
+                        if let Some(shift) = current_shift.as_mut() {
+                            shift
+                                .naps
+                                .push(start.time..end_of_shift_hour(start.time, Some(event.time)));
+                        }
+                    }
+
+                    shifts.extend(current_shift.take());
+                    current_shift = Some(Shift {
+                        guard_id,
+                        begins: event.time,
+                        naps: vec![],
+                    });
+                }
+                (_, None) => {
+                    issues.push(LogIssue {
+                        location: event.location,
+                        kind: IssueKind::BeforeFirstShift,
+                    });
+                }
+                (EventType::FallsAsleep, Some(_)) => match sleep_start {
+                    Some(start) => issues.push(LogIssue {
+                        location: event.location,
+                        kind: IssueKind::DoubleSleep {
+                            since: start.location,
+                        },
+                    }),
+                    None => sleep_start = Some(event),
+                },
+                (EventType::WakesUp, Some(shift)) => match sleep_start.take() {
+                    Some(start) => shift.naps.push(start.time..event.time),
+                    None => issues.push(LogIssue {
+                        location: event.location,
+                        kind: IssueKind::WakeWithoutSleep,
+                    }),
+                },
+            }
+        }
+
+        if let Some(start) = sleep_start.take() {
+            issues.push(LogIssue {
+                location: start.location,
+                kind: IssueKind::NeverWakes,
+            });
+            if let Some(shift) = current_shift.as_mut() {
+                shift
+                    .naps
+                    .push(start.time..end_of_shift_hour(start.time, None));
+            }
+        }
+
+        shifts.extend(current_shift.take());
+
+        let mut guards: HashMap<u32, Vec<Range<NaiveDateTime>>> = HashMap::new();
+        for shift in &shifts {
+            guards
+                .entry(shift.guard_id)
+                .or_default()
+                .extend(shift.naps.iter().cloned());
+        }
+
+        (GuardLog { shifts, guards }, issues)
+    }
+}
+
+/// Reads, sorts and assembles a guard log for the command line tools; see `sorted_events`. With
+/// `validate`, every inconsistency is printed and the process exits. Otherwise inconsistencies
+/// abort the process unless `repair` is set, in which case they are reported as warnings and
+/// repaired. Lines that are not valid events count as inconsistencies, and are skipped when
+/// repairing.
+pub fn load_guard_log(
+    sources: Vec<EventSource>,
+    chunk_size: usize,
+    validate: bool,
+    repair: bool,
+) -> GuardLog {
+    let names: Vec<_> = sources.iter().map(|s| s.name.clone()).collect();
+    let mut events = sorted_events(sources, chunk_size);
+    let mut issues = std::mem::take(&mut events.issues);
+    let (log, log_issues) = GuardLog::assemble(events);
+    issues.extend(log_issues);
+    issues.sort_by_key(|issue| issue.location);
+
+    if validate {
+        for issue in &issues {
+            println!("{}", issue.display(&names));
+        }
+
+        if issues.is_empty() {
+            println!("No inconsistencies found");
+            std::process::exit(0);
+        }
+        std::process::exit(1);
+    }
+
+    for issue in &issues {
+        eprintln!(
+            "{}: {}",
+            if repair { "warning" } else { "error" },
+            issue.display(&names)
+        );
+    }
+
+    if !repair && !issues.is_empty() {
+        eprintln!("Guard log is inconsistent; run with --repair to repair it");
+        std::process::exit(1);
+    }
+
+    log
+}
+
+/// The minute of the day, from 0 (00:00) to 1439 (23:59).
+pub fn minute_of_day(time: &NaiveDateTime) -> usize {
+    (time.hour() * 60 + time.minute()) as usize
+}
+
+pub fn sleep_minutes(sleep_times: &[Range<NaiveDateTime>]) -> u32 {
+    sleep_times
+        .iter()
+        .map(|range| (range.end - range.start).num_minutes() as u32)
+        .sum()
+}
+
+/// How many times a guard was asleep during each minute of the day. A nap longer than a day
+/// counts once for every time it passes over a minute.
+pub fn minute_histogram(sleep_times: &[Range<NaiveDateTime>]) -> [u32; MINUTES_PER_DAY] {
+    let mut sleep_counts = [0; MINUTES_PER_DAY];
+    for range in sleep_times {
+        let start = minute_of_day(&range.start);
+        let minutes = (range.end - range.start).num_minutes() as usize;
+        for x in start..start + minutes {
+            sleep_counts[x % MINUTES_PER_DAY] += 1;
+        }
+    }
+    sleep_counts
+}
@@ -0,0 +1,144 @@
+use std::io::{self, Write};
+
+use chrono::{Duration, NaiveTime};
+use serde::Serialize;
+
+use crate::strategy::{format_minute, GuardStats};
+use crate::{sleep_minutes, GuardLog};
+
+/// One shift's row of the sleep chart.
+#[derive(Clone, Debug, Serialize)]
+pub struct NightRow {
+    pub date: String,
+    pub guard_id: u32,
+    pub minutes_asleep: u32,
+    /// One character per minute of the midnight hour: `#` if the guard was asleep, `.` if not.
+    pub strip: String,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    nights: &'a [NightRow],
+    guards: &'a [GuardStats],
+}
+
+/// One row per shift, in chronological order.
+pub fn night_rows(log: &GuardLog) -> Vec<NightRow> {
+    let mut shifts: Vec<_> = log.shifts.iter().collect();
+    shifts.sort_by_key(|s| s.begins);
+
+    shifts
+        .into_iter()
+        .map(|shift| {
+            let midnight = shift.night().and_time(NaiveTime::MIN);
+            let strip = (0..60)
+                .map(|m| {
+                    let minute = midnight + Duration::minutes(m);
+                    if shift.naps.iter().any(|nap| nap.contains(&minute)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+
+            NightRow {
+                date: shift.night().to_string(),
+                guard_id: shift.guard_id,
+                minutes_asleep: sleep_minutes(&shift.naps),
+                strip,
+            }
+        })
+        .collect()
+}
+
+/// The puzzle's sleep chart followed by a table of per-guard totals.
+pub fn write_text<W: Write>(
+    writer: &mut W,
+    nights: &[NightRow],
+    guards: &[GuardStats],
+) -> io::Result<()> {
+    let tens: String = (0..60).map(|m| (b'0' + m / 10) as char).collect();
+    let ones: String = (0..60).map(|m| (b'0' + m % 10) as char).collect();
+
+    writeln!(writer, "{:<10}  {:<8}  Minute", "Date", "ID")?;
+    writeln!(writer, "{:<10}  {:<8}  {}", "", "", tens)?;
+    writeln!(writer, "{:<10}  {:<8}  {}", "", "", ones)?;
+    for night in nights {
+        writeln!(
+            writer,
+            "{:<10}  {:<8}  {}",
+            night.date,
+            format!("#{}", night.guard_id),
+            night.strip
+        )?;
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{:<8}  {:>6}  {:>6}  {:>6}  {:>7}  {:>7}  {:>6}  {:>9}",
+        "ID", "Shifts", "Nights", "Asleep", "Longest", "Average", "Minute", "Frequency"
+    )?;
+    for guard in guards {
+        writeln!(
+            writer,
+            "{:<8}  {:>6}  {:>6}  {:>6}  {:>7}  {:>7.1}  {:>6}  {:>9}",
+            format!("#{}", guard.guard_id),
+            guard.shifts,
+            guard.nights_asleep,
+            guard.minutes_asleep,
+            guard.longest_nap,
+            guard.average_per_shift,
+            format_minute(guard.most_slept_minute),
+            guard.frequency
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn write_nights_csv<W: Write>(writer: &mut W, nights: &[NightRow]) -> io::Result<()> {
+    writeln!(writer, "date,guard_id,minutes_asleep,strip")?;
+    for night in nights {
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            night.date, night.guard_id, night.minutes_asleep, night.strip
+        )?;
+    }
+    Ok(())
+}
+
+pub fn write_guards_csv<W: Write>(writer: &mut W, guards: &[GuardStats]) -> io::Result<()> {
+    writeln!(
+        writer,
+        "guard_id,shifts,nights_asleep,minutes_asleep,longest_nap,average_per_shift,most_slept_minute,frequency"
+    )?;
+    for guard in guards {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{}",
+            guard.guard_id,
+            guard.shifts,
+            guard.nights_asleep,
+            guard.minutes_asleep,
+            guard.longest_nap,
+            guard.average_per_shift,
+            guard
+                .most_slept_minute
+                .map_or(String::new(), |m| m.to_string()),
+            guard.frequency
+        )?;
+    }
+    Ok(())
+}
+
+pub fn write_json<W: Write>(
+    writer: &mut W,
+    nights: &[NightRow],
+    guards: &[GuardStats],
+) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, &Report { nights, guards })?;
+    writeln!(writer)
+}
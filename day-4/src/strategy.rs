@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{minute_histogram, sleep_minutes, GuardLog};
+
+/// Everything a strategy may rank a guard by.
+#[derive(Clone, Debug, Serialize)]
+pub struct GuardStats {
+    pub guard_id: u32,
+    pub shifts: usize,
+    /// Shifts during which the guard slept at all.
+    pub nights_asleep: usize,
+    pub minutes_asleep: u32,
+    pub longest_nap: u32,
+    pub average_per_shift: f64,
+    /// The minute of the day the guard was most often asleep, earliest first on ties. `None` if
+    /// the guard never slept.
+    pub most_slept_minute: Option<usize>,
+    pub frequency: u32,
+}
+
+/// Statistics for every guard in the log, ordered by guard id.
+pub fn guard_stats(log: &GuardLog) -> Vec<GuardStats> {
+    let mut shifts: HashMap<u32, Vec<_>> = HashMap::new();
+    for shift in &log.shifts {
+        shifts.entry(shift.guard_id).or_default().push(shift);
+    }
+
+    let mut stats: Vec<_> = shifts
+        .into_par_iter()
+        .map(|(guard_id, shifts)| {
+            let naps = &log.guards[&guard_id];
+
+            let (minute, frequency) = minute_histogram(naps)
+                .iter()
+                .cloned()
+                .enumerate()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+                .unwrap();
+
+            let minutes_asleep = sleep_minutes(naps);
+
+            GuardStats {
+                guard_id,
+                shifts: shifts.len(),
+                nights_asleep: shifts.iter().filter(|s| !s.naps.is_empty()).count(),
+                minutes_asleep,
+                longest_nap: naps
+                    .iter()
+                    .map(|nap| (nap.end - nap.start).num_minutes() as u32)
+                    .max()
+                    .unwrap_or(0),
+                average_per_shift: minutes_asleep as f64 / shifts.len() as f64,
+                most_slept_minute: if frequency > 0 { Some(minute) } else { None },
+                frequency,
+            }
+        })
+        .collect();
+
+    stats.sort_by_key(|s| s.guard_id);
+    stats
+}
+
+/// A way of choosing the guard to sneak past. Strategies rank guards by comparing their
+/// statistics; the greater guard is the better pick.
+pub struct Strategy {
+    pub name: &'static str,
+    pub compare: fn(&GuardStats, &GuardStats) -> Ordering,
+}
+
+pub const STRATEGIES: &[Strategy] = &[
+    Strategy {
+        name: "most-total-sleep",
+        compare: |a, b| a.minutes_asleep.cmp(&b.minutes_asleep),
+    },
+    Strategy {
+        name: "most-frequent-minute",
+        compare: |a, b| a.frequency.cmp(&b.frequency),
+    },
+    Strategy {
+        name: "longest-nap",
+        compare: |a, b| a.longest_nap.cmp(&b.longest_nap),
+    },
+    Strategy {
+        name: "most-nights-asleep",
+        compare: |a, b| a.nights_asleep.cmp(&b.nights_asleep),
+    },
+    Strategy {
+        name: "highest-average",
+        compare: |a, b| {
+            a.average_per_shift
+                .partial_cmp(&b.average_per_shift)
+                .unwrap()
+        },
+    },
+];
+
+impl Strategy {
+    pub fn names() -> Vec<&'static str> {
+        STRATEGIES.iter().map(|s| s.name).collect()
+    }
+
+    pub fn by_name(name: &str) -> Option<&'static Strategy> {
+        STRATEGIES.iter().find(|s| s.name == name)
+    }
+
+    /// Every guard, best pick first. Guards the strategy considers equal are ordered by id.
+    pub fn rank(&self, log: &GuardLog) -> Vec<GuardStats> {
+        let mut stats = guard_stats(log);
+        stats.sort_by(|a, b| (self.compare)(b, a).then(a.guard_id.cmp(&b.guard_id)));
+        stats
+    }
+
+    /// The puzzle answer: the best guard's id multiplied by the minute they were most often
+    /// asleep.
+    pub fn answer(&self, log: &GuardLog) -> usize {
+        let best = self.rank(log).into_iter().next().expect("Zero guards...");
+        let minute = best
+            .most_slept_minute
+            .unwrap_or_else(|| panic!("Guard #{} never slept", best.guard_id));
+
+        best.guard_id as usize * minute
+    }
+}
+
+pub fn format_minute(minute: Option<usize>) -> String {
+    match minute {
+        Some(m) => format!("{:02}:{:02}", m / 60, m % 60),
+        None => "-".to_owned(),
+    }
+}
+
+pub fn write_ranking<W: Write>(writer: &mut W, ranking: &[GuardStats]) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{:>4}  {:<8}  {:>6}  {:>6}  {:>6}  {:>7}  {:>7}  {:>6}  {:>9}",
+        "Rank", "ID", "Shifts", "Nights", "Asleep", "Longest", "Average", "Minute", "Frequency"
+    )?;
+    for (rank, guard) in ranking.iter().enumerate() {
+        writeln!(
+            writer,
+            "{:>4}  {:<8}  {:>6}  {:>6}  {:>6}  {:>7}  {:>7.1}  {:>6}  {:>9}",
+            rank + 1,
+            format!("#{}", guard.guard_id),
+            guard.shifts,
+            guard.nights_asleep,
+            guard.minutes_asleep,
+            guard.longest_nap,
+            guard.average_per_shift,
+            format_minute(guard.most_slept_minute),
+            guard.frequency
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use day_6::{Labeller, Manhattan, Point, Voronoi};
+
+/// `count` sites scattered over a `span` by `span` square, the same ones on every run.
+fn sites(count: usize, span: u64) -> Vec<Point> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % span) as i64
+    };
+    (0..count)
+        .map(|_| Point {
+            x: next(),
+            y: next(),
+        })
+        .collect()
+}
+
+fn labelling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("labelling");
+    group.sample_size(10);
+
+    for &count in &[50, 1000, 4000] {
+        let sites = sites(count, 400);
+        for &(name, labeller) in &[
+            ("brute-force", Labeller::BruteForce),
+            ("bfs", Labeller::Bfs),
+            ("kd-tree", Labeller::KdTree),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, count), &sites, |b, sites| {
+                b.iter(|| Voronoi::with_labeller(sites.clone(), &Manhattan, labeller).unwrap())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, labelling);
+criterion_main!(benches);
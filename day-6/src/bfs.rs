@@ -0,0 +1,76 @@
+use ndarray::Array2;
+
+use crate::{Cell, Point};
+
+/// Labels a `dims` grid starting at `origin` by flooding outward from every site at once, one
+/// layer of `steps` at a time. A cell reached in the same layer from different sites, or from a
+/// tied cell, is tied.
+pub fn flood(
+    sites: &[Point],
+    origin: Point,
+    dims: (usize, usize),
+    steps: &[(i32, i32)],
+) -> Array2<Cell> {
+    let mut cells: Array2<Option<Cell>> = Array2::from_elem(dims, None);
+
+    let mut frontier = vec![];
+    for (site, point) in sites.iter().enumerate() {
+        let index = [(point.x - origin.x) as usize, (point.y - origin.y) as usize];
+        let owned = Cell::Owned { site, distance: 0 };
+        cells[index] = Some(match cells[index] {
+            Some(cell) => merge(cell, owned),
+            None => owned,
+        });
+        frontier.push(index);
+    }
+    frontier.sort_unstable();
+    frontier.dedup();
+
+    let mut distance = 0;
+    while !frontier.is_empty() {
+        distance += 1;
+
+        let mut next = vec![];
+        for [x, y] in frontier {
+            let reached = match cells[[x, y]].unwrap() {
+                Cell::Owned { site, .. } => Cell::Owned { site, distance },
+                Cell::Tied { .. } => Cell::Tied { distance },
+            };
+
+            for (dx, dy) in steps {
+                let (nx, ny) = (x as i64 + *dx as i64, y as i64 + *dy as i64);
+                if nx < 0 || ny < 0 || nx >= dims.0 as i64 || ny >= dims.1 as i64 {
+                    continue;
+                }
+
+                let neighbour = [nx as usize, ny as usize];
+                match cells[neighbour] {
+                    None => {
+                        cells[neighbour] = Some(reached);
+                        next.push(neighbour);
+                    }
+                    Some(cell) if cell.distance() == distance => {
+                        cells[neighbour] = Some(merge(cell, reached));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        frontier = next;
+    }
+
+    cells.mapv(|cell| cell.expect("Flood did not reach every cell"))
+}
+
+/// Combines two labels for the same cell at the same distance.
+fn merge(a: Cell, b: Cell) -> Cell {
+    match (a, b) {
+        (Cell::Owned { site: a, distance }, Cell::Owned { site: b, .. }) if a == b => {
+            Cell::Owned { site: a, distance }
+        }
+        _ => Cell::Tied {
+            distance: a.distance(),
+        },
+    }
+}
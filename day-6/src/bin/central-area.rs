@@ -1,15 +1,63 @@
-use std::cmp::{max, min};
 use std::io::{prelude::*, BufReader};
 
-use day_6::Point;
-use rayon::prelude::*;
+use day_6::nd;
+use day_6::region::{manhattan_area, SafeRegion};
+use day_6::SpanError;
+
+fn print_yes_no(label: &str, value: bool) {
+    println!("{}: {}", label, if value { "yes" } else { "no" });
+}
+
+fn central_area<const D: usize>(
+    input: &str,
+    threshold: i64,
+    report: bool,
+) -> Result<(), SpanError> {
+    let points = nd::parse_points::<D>(input);
+
+    println!("{}", manhattan_area(&points, threshold)?);
+
+    if report {
+        let region = SafeRegion::new(&points, threshold)?;
+        match region.bounding_box() {
+            Some((lower, upper)) => println!("Bounding box: {} to {}", lower, upper),
+            None => println!("Bounding box: -"),
+        }
+        print_yes_no("Connected", region.is_connected());
+        match region.centroid() {
+            Some(centroid) => {
+                let coords: Vec<_> = centroid.iter().map(|c| format!("{:.2}", c)).collect();
+                println!("Centroid: ({})", coords.join(", "));
+            }
+            None => println!("Centroid: -"),
+        }
+    }
+
+    Ok(())
+}
 
 fn main() {
-    let matches = clap::App::new("max-finite-area")
-        .arg(clap::Arg::with_name("input").required(false))
+    let matches = clap::App::new("central-area")
+        .arg(
+            clap::Arg::with_name("input")
+                .required(false)
+                .help("One point per line, with 1 to 4 comma-separated coordinates"),
+        )
+        .arg(
+            clap::Arg::with_name("threshold")
+                .long("threshold")
+                .takes_value(true)
+                .default_value("10000")
+                .help("Count cells whose total distance to every point is less than this"),
+        )
+        .arg(
+            clap::Arg::with_name("report")
+                .long("report")
+                .help("Also print the region's bounding box, connectivity and centroid"),
+        )
         .get_matches();
 
-    let mut reader: BufReader<Box<Read>> = match matches.value_of("input") {
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
         Some(input_file) => BufReader::new(Box::new(
             std::fs::File::open(input_file).expect("Could not open input file"),
         )),
@@ -19,54 +67,29 @@ fn main() {
     let mut input_deck = String::new();
     reader.read_to_string(&mut input_deck).unwrap();
 
-    let mut points = input_deck
-        .as_str()
-        .par_lines()
-        .map(Point::from_line)
-        .collect::<Vec<_>>();
-
-    let (lower_bounds, upper_bounds) = points.par_iter().map(|p| (*p, *p)).reduce(
-        || {
-            (
-                Point {
-                    x: i32::max_value(),
-                    y: i32::max_value(),
-                },
-                Point {
-                    x: i32::min_value(),
-                    y: i32::min_value(),
-                },
-            )
-        },
-        |a, b| {
-            (
-                Point {
-                    x: min(a.0.x, b.0.x),
-                    y: min(a.0.y, b.0.y),
-                },
-                Point {
-                    x: max(a.1.x, b.1.x),
-                    y: max(a.1.y, b.1.y),
-                },
-            )
-        },
-    );
-
-    let x_dims = upper_bounds.x - lower_bounds.x + 1;
-    let y_dims = upper_bounds.y - lower_bounds.y + 1;
+    let threshold: i64 = matches
+        .value_of("threshold")
+        .unwrap()
+        .parse()
+        .expect("--threshold must be an integer");
+    let report = matches.is_present("report");
 
-    points.par_iter_mut().for_each(|p| {
-        // Shift the points to make this easier
-        p.x -= lower_bounds.x;
-        p.y -= lower_bounds.y;
-    });
-
-    let count: usize = (0..x_dims)
-        .into_par_iter()
-        .flat_map(|i| (0..y_dims).into_par_iter().map(move |j| (i, j)))
-        .map(|(x, y)| points.iter().map(|p| p.dist_from(&Point { x, y })).sum())
-        .filter(|dist: &i32| *dist < 10000)
-        .count();
+    let result = match nd::dimension(&input_deck) {
+        1 => central_area::<1>(&input_deck, threshold, report),
+        2 => central_area::<2>(&input_deck, threshold, report),
+        3 => central_area::<3>(&input_deck, threshold, report),
+        4 => central_area::<4>(&input_deck, threshold, report),
+        dimension => {
+            eprintln!(
+                "Points have {} coordinates, but only 1 to 4 are supported",
+                dimension
+            );
+            std::process::exit(1);
+        }
+    };
 
-    println!("{}", count);
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
 }
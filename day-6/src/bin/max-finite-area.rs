@@ -1,17 +1,66 @@
-use std::cmp::{max, min};
 use std::io::{prelude::*, BufReader};
 
-use day_6::Point;
-use rayon::prelude::*;
+use day_6::nd::{self, VoronoiN};
+use day_6::{
+    largest_finite_area, parse_points, scanline, Chebyshev, Euclidean, Labeller, Manhattan, Metric,
+    Point, SpanError, Voronoi, METRIC_NAMES,
+};
+
+/// With no labeller, areas are counted row by row instead of on a grid.
+fn largest_area<M: Metric>(
+    points: Vec<Point>,
+    metric: &M,
+    labeller: Option<Labeller>,
+) -> Result<Option<(usize, u128)>, SpanError> {
+    Ok(match labeller {
+        Some(labeller) => Voronoi::with_labeller(points, metric, labeller)?
+            .largest_finite_area()
+            .map(|(site, area)| (site, area as u128)),
+        None => largest_finite_area(
+            &scanline::areas(&points, metric)?,
+            &metric.infinite_regions(&points),
+        ),
+    })
+}
 
-use ndarray::prelude::*;
+fn largest_area_nd<const D: usize>(input: &str) -> Result<Option<(usize, u128)>, SpanError> {
+    Ok(VoronoiN::new(nd::parse_points::<D>(input))?
+        .largest_finite_area()
+        .map(|(site, area)| (site, area as u128)))
+}
 
 fn main() {
     let matches = clap::App::new("max-finite-area")
-        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("input")
+                .required(false)
+                .help("One point per line, with 1 to 4 comma-separated coordinates"),
+        )
+        .arg(
+            clap::Arg::with_name("metric")
+                .long("metric")
+                .takes_value(true)
+                .possible_values(METRIC_NAMES)
+                .default_value("manhattan")
+                .help("How to measure the distance between points"),
+        )
+        .arg(
+            clap::Arg::with_name("labeller")
+                .long("labeller")
+                .takes_value(true)
+                .possible_values(Labeller::NAMES)
+                .default_value("kd-tree")
+                .help("How to find the nearest point to each cell; bfs does not support euclidean"),
+        )
+        .arg(
+            clap::Arg::with_name("sparse")
+                .long("sparse")
+                .conflicts_with("labeller")
+                .help("Count areas row by row without building the grid, for far-apart points"),
+        )
         .get_matches();
 
-    let mut reader: BufReader<Box<Read>> = match matches.value_of("input") {
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
         Some(input_file) => BufReader::new(Box::new(
             std::fs::File::open(input_file).expect("Could not open input file"),
         )),
@@ -21,198 +70,70 @@ fn main() {
     let mut input_deck = String::new();
     reader.read_to_string(&mut input_deck).unwrap();
 
-    let points = input_deck
-        .as_str()
-        .par_lines()
-        .map(Point::from_line)
-        .collect::<Vec<_>>();
-
-    let (lower_bounds, upper_bounds) = points.par_iter().map(|p| (*p, *p)).reduce(
-        || {
-            (
-                Point {
-                    x: i32::max_value(),
-                    y: i32::max_value(),
-                },
-                Point {
-                    x: i32::min_value(),
-                    y: i32::min_value(),
-                },
-            )
-        },
-        |a, b| {
-            (
-                Point {
-                    x: min(a.0.x, b.0.x),
-                    y: min(a.0.y, b.0.y),
-                },
-                Point {
-                    x: max(a.1.x, b.1.x),
-                    y: max(a.1.y, b.1.y),
-                },
+    let dimension = nd::dimension(&input_deck);
+    let largest = if dimension == 2 {
+        let points = parse_points(&input_deck);
+
+        let labeller: Option<Labeller> = if matches.is_present("sparse") {
+            None
+        } else {
+            Some(matches.value_of("labeller").unwrap().parse().unwrap())
+        };
+        let metric = matches.value_of("metric").unwrap();
+        if labeller == Some(Labeller::Bfs) && metric == "euclidean" {
+            clap::Error::with_description(
+                "The bfs labeller does not support the euclidean metric",
+                clap::ErrorKind::ArgumentConflict,
             )
-        },
-    );
-
-    let box_dims = [
-        (upper_bounds.x - lower_bounds.x + 1) as usize,
-        (upper_bounds.y - lower_bounds.y + 1) as usize,
-    ];
-
-    #[derive(Copy, Clone, Debug)]
-    enum Dist {
-        Closest { point: usize, distance: i32 },
-        Many { distance: i32 },
-    }
-
-    let closests = points
-        .par_iter()
-        .cloned()
-        .map(|mut p| {
-            // Shift the points to make this easier
-            p.x -= lower_bounds.x;
-            p.y -= lower_bounds.y;
-            p
-        })
-        .enumerate()
-        .fold(
-            || Array2::default(box_dims),
-            |mut closests: Array2<Option<Dist>>, point: (usize, Point)| {
-                for ((x, y), marker) in closests.indexed_iter_mut() {
-                    let distance = point.1.dist_from(&Point {
-                        x: x as i32,
-                        y: y as i32,
-                    });
-
-                    match marker {
-                        Some(Dist::Many {
-                            distance: other_distance,
-                        }) => {
-                            if distance < *other_distance {
-                                *marker = Some(Dist::Closest {
-                                    point: point.0,
-                                    distance,
-                                })
-                            }
-                        }
-                        Some(Dist::Closest {
-                            distance: other_distance,
-                            ..
-                        }) => {
-                            if distance == *other_distance {
-                                *marker = Some(Dist::Many { distance });
-                            } else if distance < *other_distance {
-                                *marker = Some(Dist::Closest {
-                                    point: point.0,
-                                    distance,
-                                })
-                            }
-                        }
-                        None => {
-                            *marker = Some(Dist::Closest {
-                                point: point.0,
-                                distance,
-                            });
-                        }
-                    }
-                }
-
-                closests
-            },
-        )
-        .reduce(
-            || Array2::default(box_dims),
-            |mut closests_a, closests_b| {
-                ndarray::Zip::from(&mut closests_a)
-                    .and(&closests_b)
-                    .apply(|a, &b| match a {
-                        Some(Dist::Many {
-                            distance: distance_a,
-                        }) => {
-                            if let Some(Dist::Closest {
-                                distance: distance_b,
-                                ..
-                            }) = b
-                            {
-                                if distance_b < *distance_a {
-                                    *a = b;
-                                }
-                            }
-                        }
-                        Some(Dist::Closest {
-                            distance: distance_a,
-                            ..
-                        }) => match b {
-                            Some(Dist::Many {
-                                distance: distance_b,
-                            }) => {
-                                if distance_b < *distance_a {
-                                    *a = b;
-                                }
-                            }
-                            Some(Dist::Closest {
-                                distance: distance_b,
-                                ..
-                            }) => {
-                                if distance_b == *distance_a {
-                                    *a = Some(Dist::Many {
-                                        distance: distance_b,
-                                    });
-                                } else if distance_b < *distance_a {
-                                    *a = b;
-                                }
-                            }
-                            None => {}
-                        },
-                        None => *a = b,
-                    });
-
-                closests_a
-            },
-        );
-
-    let mut is_disqualified = vec![false; points.len()];
-
-    // Once an area reaches the "edge" of the bounding box, it's home free: the closest point going
-    // outward will always be that area. So let
-    for disqualified in closests
-        .row(0)
-        .iter()
-        .chain(closests.row(closests.rows() - 1).iter())
-        .chain(closests.column(0).iter())
-        .chain(closests.column(closests.cols() - 1).iter())
-        .filter_map(|closest| match closest {
-            Some(Dist::Many { .. }) => None,
-            Some(Dist::Closest { point, .. }) => Some(*point),
-            None => panic!("Not all grid points were filled!"),
-        })
-    {
-        is_disqualified[disqualified] = true;
-    }
-
-    let mut area_counts = vec![0; points.len()];
-    for closest in &closests {
-        match closest {
-            Some(Dist::Closest { point, .. }) => {
-                area_counts[*point] += 1;
-            }
-            _ => {}
+            .exit();
+        }
+        match metric {
+            "manhattan" => largest_area(points, &Manhattan, labeller),
+            "euclidean" => largest_area(points, &Euclidean, labeller),
+            "chebyshev" => largest_area(points, &Chebyshev, labeller),
+            _ => unreachable!(),
+        }
+    } else {
+        let usage_error = |message: &str| {
+            clap::Error::with_description(message, clap::ErrorKind::ArgumentConflict).exit()
+        };
+        if matches.value_of("metric") != Some("manhattan") {
+            usage_error(&format!(
+                "Only the manhattan metric supports {}-dimensional points",
+                dimension
+            ));
+        }
+        if matches.occurrences_of("labeller") != 0 || matches.is_present("sparse") {
+            usage_error("--labeller and --sparse only support 2-dimensional points");
         }
-    }
 
-    let (point, area) = area_counts
-        .par_iter()
-        .enumerate()
-        .zip(is_disqualified.par_iter())
-        .filter_map(|((point, &count), &disqualified)| {
-            if disqualified {
-                None
-            } else {
-                Some((point, count))
+        match dimension {
+            1 => largest_area_nd::<1>(&input_deck),
+            3 => largest_area_nd::<3>(&input_deck),
+            4 => largest_area_nd::<4>(&input_deck),
+            _ => {
+                eprintln!(
+                    "Points have {} coordinates, but only 1 to 4 are supported",
+                    dimension
+                );
+                std::process::exit(1);
             }
-        })
-        .max_by_key(|p| p.1)
-        .unwrap();
+        }
+    };
 
-    println!("{}: {}", point, area);
+    let largest = largest.unwrap_or_else(|error| {
+        if dimension == 2 && !matches.is_present("sparse") {
+            eprintln!("error: {}; try --sparse", error);
+        } else {
+            eprintln!("error: {}", error);
+        }
+        std::process::exit(1);
+    });
+    match largest {
+        Some((point, area)) => println!("{}: {}", point, area),
+        None => {
+            eprintln!("Every area is infinite");
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,377 @@
+use std::cmp::{max, min};
+use std::io::prelude::*;
+use std::io::{BufReader, BufWriter};
+
+use day_6::nd::PointN;
+use day_6::region::SafeRegion;
+use day_6::{
+    closest_site, parse_points, Cell, Chebyshev, Euclidean, Manhattan, Metric, Point, SpanError,
+    Voronoi, METRIC_NAMES,
+};
+
+const TIE_COLOUR: [u8; 3] = [128, 128, 128];
+const SITE_COLOUR: [u8; 3] = [0, 0, 0];
+const SAFE_COLOUR: [u8; 3] = [255, 255, 255];
+
+// Hatching stripes run diagonally, HATCH_WIDTH pixels out of every HATCH_SPACING.
+const HATCH_SPACING: usize = 8;
+const HATCH_WIDTH: usize = 2;
+
+// Successive sites step around the colour wheel by the golden angle, so neighbouring indices get
+// clearly different hues.
+fn site_colour(site: usize) -> [u8; 3] {
+    let hue = (site as f32 * 0.618_034).fract() * 6.;
+    let x = 1. - (hue % 2. - 1.).abs();
+    let (r, g, b) = match hue as usize {
+        0 => (1., x, 0.),
+        1 => (x, 1., 0.),
+        2 => (0., 1., x),
+        3 => (0., x, 1.),
+        4 => (x, 0., 1.),
+        _ => (1., 0., x),
+    };
+    let channel = |c: f32| (55. + c * 200.).round() as u8;
+    [channel(r), channel(g), channel(b)]
+}
+
+fn blend(colour: [u8; 3], over: [u8; 3], alpha: f32) -> [u8; 3] {
+    let mut blended = [0; 3];
+    for c in 0..3 {
+        blended[c] = (colour[c] as f32 * (1. - alpha) + over[c] as f32 * alpha).round() as u8;
+    }
+    blended
+}
+
+/// How to paint one cell of the plane.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Paint {
+    colour: [u8; 3],
+    hatched: bool,
+    safe: bool,
+}
+
+/// Every cell of the rendered area, and the sites to mark on top of them.
+struct Canvas {
+    origin: Point,
+    width: usize,
+    height: usize,
+    paints: Vec<Paint>,
+    sites: Vec<Point>,
+}
+
+impl Canvas {
+    fn paint(&self, x: usize, y: usize) -> Paint {
+        self.paints[y * self.width + x]
+    }
+
+    fn site_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.sites.iter().map(move |site| {
+            (
+                (site.x - self.origin.x) as usize,
+                (site.y - self.origin.y) as usize,
+            )
+        })
+    }
+}
+
+/// Paints the sites' bounding box, grown to hold the safe region if there is one. The safe region
+/// is the one `central-area` counts, so it is always measured by Manhattan distance.
+fn paint<M: Metric>(
+    sites: Vec<Point>,
+    metric: &M,
+    threshold: Option<i64>,
+) -> Result<Canvas, SpanError> {
+    let region = match threshold {
+        Some(threshold) => {
+            let sites: Vec<PointN<2>> = sites.iter().map(|s| PointN::from(*s)).collect();
+            Some(SafeRegion::new(&sites, threshold)?)
+        }
+        None => None,
+    };
+    let voronoi = Voronoi::new(sites, metric)?;
+
+    // Finite regions may reach past the sites' bounding box, so draw the whole labelled grid.
+    let (rows, cols) = voronoi.cells.dim();
+    let mut lower = voronoi.origin;
+    let mut upper = Point {
+        x: lower.x + rows as i64 - 1,
+        y: lower.y + cols as i64 - 1,
+    };
+    if let Some((region_lower, region_upper)) = region.as_ref().and_then(|r| r.bounding_box()) {
+        let (region_lower, region_upper) = (Point::from(region_lower), Point::from(region_upper));
+        lower = Point {
+            x: min(lower.x, region_lower.x),
+            y: min(lower.y, region_lower.y),
+        };
+        upper = Point {
+            x: max(upper.x, region_upper.x),
+            y: max(upper.y, region_upper.y),
+        };
+    }
+
+    let width = (upper.x - lower.x + 1) as usize;
+    let height = (upper.y - lower.y + 1) as usize;
+
+    let mut paints = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let point = Point {
+                x: lower.x + x as i64,
+                y: lower.y + y as i64,
+            };
+            // The safe region may reach outside the Voronoi grid.
+            let cell = voronoi
+                .cell_at(&point)
+                .cloned()
+                .unwrap_or_else(|| closest_site(&voronoi.sites, metric, &point));
+            let safe = region
+                .as_ref()
+                .is_some_and(|r| r.contains(&PointN::from(point)));
+
+            paints.push(match cell {
+                Cell::Owned { site, .. } => Paint {
+                    colour: site_colour(site),
+                    hatched: voronoi.is_infinite(site),
+                    safe,
+                },
+                Cell::Tied { .. } => Paint {
+                    colour: TIE_COLOUR,
+                    hatched: false,
+                    safe,
+                },
+            });
+        }
+    }
+
+    Ok(Canvas {
+        origin: lower,
+        width,
+        height,
+        paints,
+        sites: voronoi.sites,
+    })
+}
+
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    fn put(&mut self, x: usize, y: usize, colour: [u8; 3]) {
+        let offset = (y * self.width + x) * 3;
+        self.pixels[offset..offset + 3].copy_from_slice(&colour);
+    }
+
+    fn write_png<W: Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)
+    }
+}
+
+fn rasterize(canvas: &Canvas, scale: usize) -> Image {
+    let mut image = Image {
+        width: canvas.width * scale,
+        height: canvas.height * scale,
+        pixels: vec![0; canvas.width * scale * canvas.height * scale * 3],
+    };
+
+    for y in 0..image.height {
+        for x in 0..image.width {
+            let paint = canvas.paint(x / scale, y / scale);
+
+            let mut colour = paint.colour;
+            if paint.hatched && (x + y) % HATCH_SPACING < HATCH_WIDTH {
+                colour = blend(colour, [0, 0, 0], 0.5);
+            }
+            if paint.safe {
+                colour = blend(colour, SAFE_COLOUR, 0.4);
+            }
+            image.put(x, y, colour);
+        }
+    }
+
+    // Markers are a cell wide, but never less than three pixels so they stay visible.
+    let radius = max(scale / 2, 1) as isize;
+    for (cx, cy) in canvas.site_cells() {
+        let centre = (
+            (cx * scale + scale / 2) as isize,
+            (cy * scale + scale / 2) as isize,
+        );
+        for y in centre.1 - radius..=centre.1 + radius {
+            for x in centre.0 - radius..=centre.0 + radius {
+                if x >= 0 && y >= 0 && (x as usize) < image.width && (y as usize) < image.height {
+                    image.put(x as usize, y as usize, SITE_COLOUR);
+                }
+            }
+        }
+    }
+
+    image
+}
+
+fn hex(colour: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+/// Writes the canvas as an SVG, with each row's runs of identically painted cells merged into a
+/// single rectangle.
+fn write_svg<W: Write>(writer: &mut W, canvas: &Canvas, scale: usize) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" shape-rendering="crispEdges">"#,
+        canvas.width * scale,
+        canvas.height * scale
+    )?;
+    writeln!(
+        writer,
+        r#"<defs><pattern id="hatch" width="{0}" height="{0}" patternUnits="userSpaceOnUse"><path d="M0,{0} L{0},0" stroke="black" stroke-opacity="0.5" stroke-width="{1}"/></pattern></defs>"#,
+        HATCH_SPACING, HATCH_WIDTH
+    )?;
+
+    for y in 0..canvas.height {
+        let mut x = 0;
+        while x < canvas.width {
+            let paint = canvas.paint(x, y);
+            let run = (x..canvas.width)
+                .take_while(|&i| canvas.paint(i, y) == paint)
+                .count();
+
+            let rect = format!(
+                r#"x="{}" y="{}" width="{}" height="{}""#,
+                x * scale,
+                y * scale,
+                run * scale,
+                scale
+            );
+            writeln!(writer, r#"<rect {} fill="{}"/>"#, rect, hex(paint.colour))?;
+            if paint.hatched {
+                writeln!(writer, r#"<rect {} fill="url(#hatch)"/>"#, rect)?;
+            }
+            if paint.safe {
+                writeln!(
+                    writer,
+                    r#"<rect {} fill="{}" fill-opacity="0.4"/>"#,
+                    rect,
+                    hex(SAFE_COLOUR)
+                )?;
+            }
+
+            x += run;
+        }
+    }
+
+    for (x, y) in canvas.site_cells() {
+        writeln!(
+            writer,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            x as f32 * scale as f32 + scale as f32 / 2.,
+            y as f32 * scale as f32 + scale as f32 / 2.,
+            max(scale / 2, 1),
+            hex(SITE_COLOUR)
+        )?;
+    }
+
+    writeln!(writer, "</svg>")
+}
+
+fn main() {
+    let matches = clap::App::new("voronoi-render")
+        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .required(true)
+                .help("Image file to write"),
+        )
+        .arg(
+            clap::Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["png", "svg"])
+                .help("Image format. Defaults to the output file's extension"),
+        )
+        .arg(
+            clap::Arg::with_name("metric")
+                .long("metric")
+                .takes_value(true)
+                .possible_values(METRIC_NAMES)
+                .default_value("manhattan")
+                .help("How to measure the distance between points"),
+        )
+        .arg(
+            clap::Arg::with_name("threshold")
+                .long("threshold")
+                .takes_value(true)
+                .help(
+                    "Overlay the region whose total Manhattan distance to every point is less \
+                     than this, whatever the metric",
+                ),
+        )
+        .arg(
+            clap::Arg::with_name("scale")
+                .long("scale")
+                .takes_value(true)
+                .default_value("4")
+                .help("Pixels per cell"),
+        )
+        .get_matches();
+
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
+        Some(input_file) => BufReader::new(Box::new(
+            std::fs::File::open(input_file).expect("Could not open input file"),
+        )),
+        None => BufReader::new(Box::new(std::io::stdin())),
+    };
+
+    let mut input_deck = String::new();
+    reader.read_to_string(&mut input_deck).unwrap();
+
+    let points = parse_points(&input_deck);
+
+    let threshold: Option<i64> = matches
+        .value_of("threshold")
+        .map(|t| t.parse().expect("--threshold must be an integer"));
+
+    let scale: usize = matches
+        .value_of("scale")
+        .unwrap()
+        .parse()
+        .expect("Scale must be a positive integer");
+    assert!(scale > 0, "Scale must be a positive integer");
+
+    let canvas = match matches.value_of("metric").unwrap() {
+        "manhattan" => paint(points, &Manhattan, threshold),
+        "euclidean" => paint(points, &Euclidean, threshold),
+        "chebyshev" => paint(points, &Chebyshev, threshold),
+        _ => unreachable!(),
+    };
+    let canvas = canvas.unwrap_or_else(|error| {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    });
+
+    let output = matches.value_of("output").unwrap();
+    let format = matches.value_of("format").unwrap_or_else(|| {
+        if output.to_ascii_lowercase().ends_with(".svg") {
+            "svg"
+        } else {
+            "png"
+        }
+    });
+
+    let mut writer =
+        BufWriter::new(std::fs::File::create(output).expect("Could not create output file"));
+
+    match format {
+        "svg" => write_svg(&mut writer, &canvas, scale).expect("Could not write SVG"),
+        _ => rasterize(&canvas, scale)
+            .write_png(writer)
+            .expect("Could not write PNG"),
+    }
+}
@@ -0,0 +1,89 @@
+//! Boxes holding every finite Euclidean region whole.
+//!
+//! A finite region is a convex polygon whose corners, the circumcentres of three sites, can lie
+//! well outside the sites' bounding box. Its cells need not be connected: a thin corner can skip
+//! a whole row and own cells further out, so searching outwards until a row comes up empty
+//! undercounts it.
+
+use std::cmp::{max, min};
+
+use crate::{bounding_box, Point};
+
+fn clamp(coord: i128) -> i64 {
+    coord.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
+/// The part of `polygon` at least as close to the origin as to `d`, where `d · p <= |d|² / 2`.
+fn clip(polygon: &[(f64, f64)], d: (f64, f64)) -> Vec<(f64, f64)> {
+    let limit = (d.0 * d.0 + d.1 * d.1) / 2.;
+    let side = |p: &(f64, f64)| d.0 * p.0 + d.1 * p.1 - limit;
+
+    let mut clipped = vec![];
+    for (i, a) in polygon.iter().enumerate() {
+        let b = &polygon[(i + 1) % polygon.len()];
+        let (side_a, side_b) = (side(a), side(b));
+        if side_a <= 0. {
+            clipped.push(*a);
+        }
+        if (side_a <= 0.) != (side_b <= 0.) {
+            let t = side_a / (side_a - side_b);
+            clipped.push((a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1)));
+        }
+    }
+    clipped
+}
+
+/// Each finite region is cut out of a square around its site by the perpendicular bisectors
+/// towards every other site. The square doubles until the region no longer touches its sides,
+/// at which point the region lies wholly inside it. The corners are found in floating point, so
+/// the box is padded by more than their rounding error.
+pub fn euclidean(sites: &[Point], infinite: &[bool]) -> (Point, Point) {
+    let (mut lower, mut upper) = bounding_box(sites);
+    let span = max(
+        i128::from(upper.x) - i128::from(lower.x),
+        i128::from(upper.y) - i128::from(lower.y),
+    );
+
+    for (site, p) in sites.iter().enumerate() {
+        // A site sharing its position with another owns nothing.
+        let duplicated = sites
+            .iter()
+            .enumerate()
+            .any(|(other, q)| other != site && q == p);
+        if infinite[site] || duplicated {
+            continue;
+        }
+
+        let offsets: Vec<(f64, f64)> = sites
+            .iter()
+            .filter(|q| *q != p)
+            .map(|q| {
+                (
+                    (i128::from(q.x) - i128::from(p.x)) as f64,
+                    (i128::from(q.y) - i128::from(p.y)) as f64,
+                )
+            })
+            .collect();
+
+        let mut half = max(span, 1) as f64;
+        let region = loop {
+            let square = vec![(-half, -half), (half, -half), (half, half), (-half, half)];
+            let region = offsets.iter().fold(square, |polygon, d| clip(&polygon, *d));
+            // Past this, the square holds every cell with coordinates in range.
+            if half > 1e20 || region.iter().all(|(x, y)| x.abs() < half && y.abs() < half) {
+                break region;
+            }
+            half *= 2.;
+        };
+
+        let pad = 1. + half * 1e-9;
+        for (x, y) in region {
+            let offset = |coord: i64, delta: f64| clamp(i128::from(coord) + delta as i128);
+            lower.x = min(lower.x, offset(p.x, (x - pad).floor()));
+            lower.y = min(lower.y, offset(p.y, (y - pad).floor()));
+            upper.x = max(upper.x, offset(p.x, (x + pad).ceil()));
+            upper.y = max(upper.y, offset(p.y, (y + pad).ceil()));
+        }
+    }
+    (lower, upper)
+}
@@ -0,0 +1,148 @@
+//! Exact tests for which sites own infinitely many cells.
+//!
+//! Far enough outside the sites' bounding box, every distance grows the same way, so ownership
+//! comes down to comparing a per-site score that depends on a single parameter: the position
+//! along the side of the box, or the diagonal offset in a corner. Between consecutive
+//! breakpoints of the scores each difference is linear, so a site wins somewhere exactly when it
+//! wins at one of the breakpoints.
+
+use std::cmp::{max, min};
+
+use crate::Point;
+
+/// The index of the strictly highest score, if there is one.
+fn unique_best<I: Iterator<Item = i128>>(scores: I) -> Option<usize> {
+    let mut best = None;
+    let mut best_score = i128::MIN;
+    let mut tied = false;
+    for (i, score) in scores.enumerate() {
+        if score > best_score {
+            best = Some(i);
+            best_score = score;
+            tied = false;
+        } else if score == best_score {
+            tied = true;
+        }
+    }
+    if tied {
+        None
+    } else {
+        best
+    }
+}
+
+/// Every site reflected so that each side or corner of the plane in turn becomes the one in the
+/// positive direction.
+fn orientations(sites: &[Point]) -> Vec<Vec<(i128, i128)>> {
+    [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+        .iter()
+        .map(|&(sx, sy)| {
+            sites
+                .iter()
+                .map(|p| (sx * i128::from(p.x), sy * i128::from(p.y)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Beyond the top of the box, at `(x, Y)`, the site at `(sx, sy)` is `Y - (sy - |x - sx|)` away.
+/// Columns far out either way cover the corners.
+pub fn manhattan(sites: &[Point]) -> Vec<bool> {
+    let mut infinite = vec![false; sites.len()];
+    for sites in orientations(sites) {
+        for &(x, y) in &sites {
+            let beyond_top = sites.iter().map(|&(sx, sy)| sy - (x - sx).abs());
+            let beyond_right = sites.iter().map(|&(sx, sy)| sx - (y - sy).abs());
+            for site in unique_best(beyond_top)
+                .into_iter()
+                .chain(unique_best(beyond_right))
+            {
+                infinite[site] = true;
+            }
+        }
+    }
+    infinite
+}
+
+/// Above and to the right of every site, at `(X, Y)` with `k = X - Y`, the site at `(sx, sy)` is
+/// `X - min(sx, sy + k)` away. Offsets far out either way cover the sides of the box.
+pub fn chebyshev(sites: &[Point]) -> Vec<bool> {
+    let mut infinite = vec![false; sites.len()];
+    for sites in orientations(sites) {
+        for &(x, y) in &sites {
+            let k = x - y;
+            let scores = sites.iter().map(|&(sx, sy)| min(sx, sy + k));
+            if let Some(site) = unique_best(scores) {
+                infinite[site] = true;
+            }
+        }
+    }
+    infinite
+}
+
+fn cross(o: &Point, a: &Point, b: &Point) -> i128 {
+    let (ax, ay) = (
+        i128::from(a.x) - i128::from(o.x),
+        i128::from(a.y) - i128::from(o.y),
+    );
+    let (bx, by) = (
+        i128::from(b.x) - i128::from(o.x),
+        i128::from(b.y) - i128::from(o.y),
+    );
+    ax * by - ay * bx
+}
+
+/// The corners of the convex hull, counter-clockwise, without collinear points.
+fn convex_hull(sites: &[Point]) -> Vec<Point> {
+    let mut points = sites.to_vec();
+    points.sort_by_key(|p| (p.x, p.y));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Point> = Vec::with_capacity(2 * points.len());
+    for pass in 0..2 {
+        let start = hull.len();
+        for p in &points {
+            while hull.len() >= start + 2
+                && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], p) <= 0
+            {
+                hull.pop();
+            }
+            hull.push(*p);
+        }
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+    hull
+}
+
+fn on_segment(a: &Point, b: &Point, p: &Point) -> bool {
+    cross(a, b, p) == 0
+        && min(a.x, b.x) <= p.x
+        && p.x <= max(a.x, b.x)
+        && min(a.y, b.y) <= p.y
+        && p.y <= max(a.y, b.y)
+}
+
+/// A site on the boundary of the convex hull strictly owns every point along the hull's outward
+/// normal from it, while a site inside the hull is hemmed in on every side. Collinear sites on
+/// the boundary own a strip that always holds lattice points. Sites sharing a position with
+/// another own nothing.
+pub fn euclidean(sites: &[Point]) -> Vec<bool> {
+    let hull = convex_hull(sites);
+
+    sites
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let duplicated = sites.iter().enumerate().any(|(j, q)| i != j && p == q);
+            let on_boundary = hull.len() < 3
+                || (0..hull.len()).any(|e| on_segment(&hull[e], &hull[(e + 1) % hull.len()], p));
+            !duplicated && on_boundary
+        })
+        .collect()
+}
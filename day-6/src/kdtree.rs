@@ -0,0 +1,83 @@
+use crate::{Cell, Metric, Point};
+
+/// A 2-d tree of sites, stored implicitly: the median of each range is its node, with the lower
+/// half of the range to its left and the upper half to its right. Depths alternate between
+/// splitting on x and on y.
+pub struct KdTree {
+    sites: Vec<(Point, usize)>,
+}
+
+fn coordinate(point: &Point, depth: usize) -> i64 {
+    match depth % 2 {
+        0 => point.x,
+        _ => point.y,
+    }
+}
+
+fn build(sites: &mut [(Point, usize)], depth: usize) {
+    if sites.len() <= 1 {
+        return;
+    }
+
+    let mid = sites.len() / 2;
+    sites.select_nth_unstable_by_key(mid, |(p, _)| coordinate(p, depth));
+
+    let (lower, upper) = sites.split_at_mut(mid);
+    build(lower, depth + 1);
+    build(&mut upper[1..], depth + 1);
+}
+
+impl KdTree {
+    pub fn new(sites: &[Point]) -> Self {
+        let mut sites: Vec<_> = sites.iter().cloned().zip(0..).collect();
+        build(&mut sites, 0);
+        KdTree { sites }
+    }
+
+    /// The nearest site to `point`, or a tie if several are equally near. Agrees with
+    /// `closest_site` for every metric.
+    pub fn closest_site<M: Metric>(&self, metric: &M, point: &Point) -> Cell {
+        let mut best = Cell::Tied {
+            distance: i128::MAX,
+        };
+        search(&self.sites, 0, metric, point, &mut best);
+        best
+    }
+}
+
+fn search<M: Metric>(
+    sites: &[(Point, usize)],
+    depth: usize,
+    metric: &M,
+    point: &Point,
+    best: &mut Cell,
+) {
+    if sites.is_empty() {
+        return;
+    }
+
+    let mid = sites.len() / 2;
+    let (site_point, site) = sites[mid];
+
+    let distance = metric.distance(&site_point, point);
+    if distance < best.distance() {
+        *best = Cell::Owned { site, distance };
+    } else if distance == best.distance() {
+        *best = Cell::Tied { distance };
+    }
+
+    let delta = i128::from(coordinate(point, depth)) - i128::from(coordinate(&site_point, depth));
+    let (lower, upper) = (&sites[..mid], &sites[mid + 1..]);
+    let (near, far) = if delta < 0 {
+        (lower, upper)
+    } else {
+        (upper, lower)
+    };
+
+    search(near, depth + 1, metric, point, best);
+    // Equally near sites must be found too, so only prune the far side when it is strictly
+    // further away.
+    if metric.axis_distance(delta.abs()) <= best.distance() {
+        search(far, depth + 1, metric, point, best);
+    }
+}
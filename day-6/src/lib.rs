@@ -1,27 +1,666 @@
-#[derive(Copy, Clone, Debug)]
+use std::cmp::{max, min};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use ndarray::Array2;
+use rayon::prelude::*;
+
+pub mod bfs;
+mod extent;
+mod infinite;
+pub mod kdtree;
+pub mod nd;
+pub mod region;
+pub mod scanline;
+
+use crate::kdtree::KdTree;
+use crate::nd::{Grid, PointN};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Point {
-    pub x: i32,
-    pub y: i32,
+    pub x: i64,
+    pub y: i64,
 }
 
 impl Point {
+    /// Parses two comma-separated coordinates; see `PointN::from_line`.
     pub fn from_line(line: &str) -> Self {
-        let mut split = line.split(", ");
-        let x = split
-            .next()
-            .expect("Point format incorrect!")
-            .parse()
-            .unwrap();
-        let y = split
-            .next()
-            .expect("Point format incorrect!")
-            .parse()
-            .unwrap();
-
-        Self { x, y }
-    }
-
-    pub fn dist_from(&self, other: &Self) -> i32 {
-        (other.x - self.x).abs() + (other.y - self.y).abs()
+        PointN::<2>::from_line(line).into()
+    }
+}
+
+impl From<PointN<2>> for Point {
+    fn from(point: PointN<2>) -> Self {
+        let [x, y] = point.coords;
+        Point { x, y }
+    }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(point: Point) -> Self {
+        PointN {
+            coords: [point.x, point.y],
+        }
+    }
+}
+
+/// Parses one point per line. Blank lines are skipped.
+pub fn parse_points(input: &str) -> Vec<Point> {
+    input
+        .par_lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(Point::from_line)
+        .collect()
+}
+
+/// The smallest box containing every point, as its lower and upper corners (both inclusive).
+pub fn bounding_box(points: &[Point]) -> (Point, Point) {
+    points.par_iter().map(|p| (*p, *p)).reduce(
+        || {
+            (
+                Point {
+                    x: i64::MAX,
+                    y: i64::MAX,
+                },
+                Point {
+                    x: i64::MIN,
+                    y: i64::MIN,
+                },
+            )
+        },
+        |a, b| {
+            (
+                Point {
+                    x: min(a.0.x, b.0.x),
+                    y: min(a.0.y, b.0.y),
+                },
+                Point {
+                    x: max(a.1.x, b.1.x),
+                    y: max(a.1.y, b.1.y),
+                },
+            )
+        },
+    )
+}
+
+/// The points are so far apart that a grid covering them cannot be indexed or held in memory, or
+/// that distances between them cannot be measured: a coordinate, an offset, a distance or the
+/// number of cells would overflow, or there would be more than `MAX_CELLS` cells.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SpanError;
+
+impl fmt::Display for SpanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the points are too far apart")
+    }
+}
+
+impl std::error::Error for SpanError {}
+
+/// The most cells a grid may have. Every cell takes several bytes, so larger grids could never be
+/// allocated; they are refused up front rather than aborting when memory runs out.
+pub const MAX_CELLS: usize = 1 << 32;
+
+/// The number of cells from `lower - margin` to `upper + margin` along one axis, if every cell's
+/// coordinate and offset from the first cell fit in an `i64`.
+pub(crate) fn axis_len(lower: i64, upper: i64, margin: i64) -> Result<usize, SpanError> {
+    let first = lower.checked_sub(margin).ok_or(SpanError)?;
+    let last = upper.checked_add(margin).ok_or(SpanError)?;
+    last.checked_sub(first)
+        .and_then(|offset| offset.checked_add(1))
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or(SpanError)
+}
+
+/// Which sides of a searched area a finite region reached: below and above it in x, then below
+/// and above it in y.
+pub(crate) type Sides = [bool; 4];
+
+/// Searches from `lower` to `upper` with `attempt`, which returns its result along with the sides
+/// a finite region reached. Each of those sides is pushed out by 1, 2, 4 cells and so on until no
+/// finite region reaches any side, as under some metrics they extend past the sites' bounding box.
+/// The box must come from `Metric::finite_extent` for this to find every finite region whole.
+pub(crate) fn grow<T, F>(lower: Point, upper: Point, mut attempt: F) -> Result<T, SpanError>
+where
+    F: FnMut(Point, Point) -> Result<(T, Sides), SpanError>,
+{
+    let mut margins = [0_i64; 4];
+    loop {
+        let from = Point {
+            x: lower.x.checked_sub(margins[0]).ok_or(SpanError)?,
+            y: lower.y.checked_sub(margins[2]).ok_or(SpanError)?,
+        };
+        let to = Point {
+            x: upper.x.checked_add(margins[1]).ok_or(SpanError)?,
+            y: upper.y.checked_add(margins[3]).ok_or(SpanError)?,
+        };
+
+        let (result, reached) = attempt(from, to)?;
+        if !reached.contains(&true) {
+            return Ok(result);
+        }
+        for (margin, reached) in margins.iter_mut().zip(reached.iter()) {
+            if *reached {
+                *margin = match *margin {
+                    0 => 1,
+                    _ => margin.checked_mul(2).ok_or(SpanError)?,
+                };
+            }
+        }
+    }
+}
+
+/// A way of measuring the distance between two points. Distances only need to be ordered the
+/// same way as the true distances, so they may be scaled or squared to stay integral.
+pub trait Metric: Sync {
+    fn distance(&self, a: &Point, b: &Point) -> i128;
+
+    /// The distance between two points `delta` apart along a single axis. Points further apart
+    /// along that axis are never closer than this, whatever the other axis does.
+    fn axis_distance(&self, delta: i128) -> i128;
+
+    /// The steps a breadth-first flood over the grid may take, if the number of steps from a
+    /// site to a cell is always its distance.
+    fn grid_steps(&self) -> Option<&'static [(i32, i32)]> {
+        None
+    }
+
+    /// Whether each site owns infinitely many cells of the plane.
+    fn infinite_regions(&self, sites: &[Point]) -> Vec<bool>;
+
+    /// The lower and upper corners of a box holding every site, from which to search for the
+    /// regions `infinite` marks as finite. Each of those regions must either lie inside the box or
+    /// be connected to it by its own cells, so that it crosses a side of the box whenever it
+    /// reaches outside. With `grid_steps`, a step towards a site brings it one closer and every
+    /// other site at most one closer, which connects each region to its site.
+    fn finite_extent(&self, sites: &[Point], _infinite: &[bool]) -> (Point, Point) {
+        bounding_box(sites)
+    }
+
+    /// The largest distance along an axis between two points that can be measured, or compared
+    /// while finding infinite regions, without overflowing.
+    fn max_span(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+/// Checks that the distances between every cell from `lower` to `upper` can be measured.
+pub(crate) fn check_span<M: Metric>(
+    metric: &M,
+    lower: &Point,
+    upper: &Point,
+) -> Result<(), SpanError> {
+    let span = |lower: i64, upper: i64| (i128::from(upper) - i128::from(lower)) as u64;
+    if span(lower.x, upper.x) <= metric.max_span() && span(lower.y, upper.y) <= metric.max_span() {
+        Ok(())
+    } else {
+        Err(SpanError)
+    }
+}
+
+/// Taxicab distance: `|dx| + |dy|`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Manhattan;
+
+/// Straight-line distance, squared: `dx² + dy²`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Euclidean;
+
+/// Chessboard distance: `max(|dx|, |dy|)`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Chebyshev;
+
+fn deltas(a: &Point, b: &Point) -> (i128, i128) {
+    (
+        (i128::from(b.x) - i128::from(a.x)).abs(),
+        (i128::from(b.y) - i128::from(a.y)).abs(),
+    )
+}
+
+impl Metric for Manhattan {
+    fn distance(&self, a: &Point, b: &Point) -> i128 {
+        let (dx, dy) = deltas(a, b);
+        dx + dy
+    }
+    fn axis_distance(&self, delta: i128) -> i128 {
+        delta.abs()
+    }
+
+    fn grid_steps(&self) -> Option<&'static [(i32, i32)]> {
+        Some(&[(-1, 0), (1, 0), (0, -1), (0, 1)])
+    }
+
+    fn infinite_regions(&self, sites: &[Point]) -> Vec<bool> {
+        infinite::manhattan(sites)
+    }
+}
+
+impl Metric for Euclidean {
+    fn distance(&self, a: &Point, b: &Point) -> i128 {
+        let (dx, dy) = deltas(a, b);
+        dx * dx + dy * dy
+    }
+    fn axis_distance(&self, delta: i128) -> i128 {
+        delta * delta
+    }
+
+    fn infinite_regions(&self, sites: &[Point]) -> Vec<bool> {
+        infinite::euclidean(sites)
+    }
+
+    /// A region can skip a whole row of cells and still own cells beyond it, so the box must hold
+    /// every finite region outright.
+    fn finite_extent(&self, sites: &[Point], infinite: &[bool]) -> (Point, Point) {
+        extent::euclidean(sites, infinite)
+    }
+
+    /// Squared distances, and the cross products used to find the convex hull, take about twice
+    /// the bits of the span and must fit in an `i128`.
+    fn max_span(&self) -> u64 {
+        1 << 62
+    }
+}
+
+impl Metric for Chebyshev {
+    fn distance(&self, a: &Point, b: &Point) -> i128 {
+        let (dx, dy) = deltas(a, b);
+        max(dx, dy)
+    }
+    fn axis_distance(&self, delta: i128) -> i128 {
+        delta.abs()
+    }
+
+    fn grid_steps(&self) -> Option<&'static [(i32, i32)]> {
+        Some(&[
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ])
+    }
+
+    fn infinite_regions(&self, sites: &[Point]) -> Vec<bool> {
+        infinite::chebyshev(sites)
+    }
+}
+
+pub const METRIC_NAMES: &[&str] = &["manhattan", "euclidean", "chebyshev"];
+
+/// Who a grid cell is closest to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Cell {
+    /// The site with index `site` is strictly closer than every other site.
+    Owned { site: usize, distance: i128 },
+    /// Two or more sites are equally close.
+    Tied { distance: i128 },
+}
+
+impl Cell {
+    pub fn owner(&self) -> Option<usize> {
+        match self {
+            Cell::Owned { site, .. } => Some(*site),
+            Cell::Tied { .. } => None,
+        }
+    }
+
+    pub fn distance(&self) -> i128 {
+        match self {
+            Cell::Owned { distance, .. } | Cell::Tied { distance } => *distance,
+        }
+    }
+}
+
+/// The nearest site to every cell of the sites' bounding box, under some metric. The box is grown
+/// until it holds every finite region whole, as under some metrics they reach outside it.
+pub struct Voronoi {
+    pub sites: Vec<Point>,
+    /// The lower corner of the grid. `cells[[x, y]]` describes the point
+    /// `(origin.x + x, origin.y + y)`.
+    pub origin: Point,
+    pub cells: Array2<Cell>,
+    infinite: Vec<bool>,
+}
+
+/// The nearest of `sites` to `point`, or a tie if several are equally near.
+pub fn closest_site<M: Metric>(sites: &[Point], metric: &M, point: &Point) -> Cell {
+    nearest(sites.iter().map(|p| metric.distance(p, point)))
+}
+
+/// The site with the smallest of `distances`, given in site order, or a tie if several share it.
+pub(crate) fn nearest<I: Iterator<Item = i128>>(distances: I) -> Cell {
+    let mut cell = Cell::Tied {
+        distance: i128::MAX,
+    };
+    for (site, distance) in distances.enumerate() {
+        if distance < cell.distance() {
+            cell = Cell::Owned { site, distance };
+        } else if distance == cell.distance() {
+            cell = Cell::Tied { distance };
+        }
+    }
+    cell
+}
+
+/// How a `Voronoi` finds the nearest site to each cell.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Labeller {
+    /// Measures the distance from every site to every cell.
+    BruteForce,
+    /// Floods the grid outward from every site at once. Only works for metrics with grid steps.
+    Bfs,
+    /// Queries a k-d tree of the sites for every cell.
+    KdTree,
+}
+
+impl Labeller {
+    pub const NAMES: &'static [&'static str] = &["brute-force", "bfs", "kd-tree"];
+}
+
+impl FromStr for Labeller {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "brute-force" => Ok(Labeller::BruteForce),
+            "bfs" => Ok(Labeller::Bfs),
+            "kd-tree" => Ok(Labeller::KdTree),
+            _ => Err(format!("Unknown labeller: {}", s)),
+        }
+    }
+}
+
+/// Labels each cell of a `dims` grid starting at `origin` in parallel.
+fn label_cells<F>(origin: Point, dims: (usize, usize), label: F) -> Array2<Cell>
+where
+    F: Fn(&Point) -> Cell + Sync,
+{
+    let cells: Vec<_> = (0..dims.0 * dims.1)
+        .into_par_iter()
+        .map(|i| {
+            label(&Point {
+                x: origin.x + (i / dims.1) as i64,
+                y: origin.y + (i % dims.1) as i64,
+            })
+        })
+        .collect();
+
+    Array2::from_shape_vec(dims, cells).unwrap()
+}
+
+impl Voronoi {
+    pub fn new<M: Metric>(sites: Vec<Point>, metric: &M) -> Result<Self, SpanError> {
+        Self::with_labeller(sites, metric, Labeller::BruteForce)
+    }
+
+    /// Panics if `labeller` is `Bfs` and `metric` has no grid steps.
+    pub fn with_labeller<M: Metric>(
+        sites: Vec<Point>,
+        metric: &M,
+        labeller: Labeller,
+    ) -> Result<Self, SpanError> {
+        assert!(!sites.is_empty(), "Zero sites...");
+
+        let (lower, upper) = bounding_box(&sites);
+        check_span(metric, &lower, &upper)?;
+        let infinite = metric.infinite_regions(&sites);
+        let (lower, upper) = metric.finite_extent(&sites, &infinite);
+
+        let (origin, cells) = grow(lower, upper, |from, to| {
+            check_span(metric, &from, &to)?;
+            let grid = Grid::around(from.into(), to.into(), 0)?;
+            let dims = (grid.dims[0], grid.dims[1]);
+
+            let cells = match labeller {
+                Labeller::BruteForce => {
+                    label_cells(from, dims, |point| closest_site(&sites, metric, point))
+                }
+                Labeller::Bfs => {
+                    let steps = metric
+                        .grid_steps()
+                        .expect("The BFS labeller does not support this metric");
+                    bfs::flood(&sites, from, dims, steps)
+                }
+                Labeller::KdTree => {
+                    let tree = KdTree::new(&sites);
+                    label_cells(from, dims, |point| tree.closest_site(metric, point))
+                }
+            };
+
+            let mut reached = [false; 4];
+            for ((x, y), cell) in cells.indexed_iter() {
+                if cell.owner().is_some_and(|site| !infinite[site]) {
+                    reached[0] |= x == 0;
+                    reached[1] |= x == dims.0 - 1;
+                    reached[2] |= y == 0;
+                    reached[3] |= y == dims.1 - 1;
+                }
+            }
+            Ok(((from, cells), reached))
+        })?;
+
+        Ok(Voronoi {
+            sites,
+            origin,
+            cells,
+            infinite,
+        })
+    }
+
+    pub fn cell_at(&self, point: &Point) -> Option<&Cell> {
+        let x = usize::try_from(i128::from(point.x) - i128::from(self.origin.x)).ok()?;
+        let y = usize::try_from(i128::from(point.y) - i128::from(self.origin.y)).ok()?;
+        self.cells.get([x, y])
+    }
+
+    /// The number of cells each site owns outright, by site index.
+    pub fn areas(&self) -> Vec<usize> {
+        let mut areas = vec![0; self.sites.len()];
+        for site in self.cells.iter().filter_map(Cell::owner) {
+            areas[site] += 1;
+        }
+        areas
+    }
+
+    /// Whether the site with index `site` owns infinitely many cells of the plane, not just of the
+    /// bounding box. Unlike checking whether its region reaches the edge of the bounding box, this
+    /// is exact for every metric.
+    ///
+    /// Under the Chebyshev metric, the middle site reaches the bottom edge of the box, but the
+    /// sites either side of it close its region off further down, and all of it is counted:
+    ///
+    /// ```
+    /// use day_6::{Chebyshev, Point, Voronoi};
+    ///
+    /// let sites = vec![
+    ///     Point { x: 0, y: 0 },
+    ///     Point { x: 10, y: 0 },
+    ///     Point { x: 5, y: 3 },
+    ///     Point { x: 5, y: 10 },
+    /// ];
+    /// let voronoi = Voronoi::new(sites, &Chebyshev).unwrap();
+    ///
+    /// assert_eq!(voronoi.cell_at(&Point { x: 5, y: 0 }).unwrap().owner(), Some(2));
+    /// assert!(!voronoi.is_infinite(2));
+    /// assert_eq!(voronoi.largest_finite_area(), Some((2, 42)));
+    /// ```
+    ///
+    /// Under the Euclidean metric, only sites on the convex hull extend forever, even when a site
+    /// inside it is the closest to part of the edge of the box and beyond:
+    ///
+    /// ```
+    /// use day_6::{Euclidean, Point, Voronoi};
+    ///
+    /// let sites = vec![
+    ///     Point { x: 0, y: 0 },
+    ///     Point { x: 10, y: 0 },
+    ///     Point { x: 5, y: 1 },
+    ///     Point { x: 5, y: 10 },
+    /// ];
+    /// let voronoi = Voronoi::new(sites, &Euclidean).unwrap();
+    ///
+    /// assert_eq!(voronoi.cell_at(&Point { x: 5, y: 0 }).unwrap().owner(), Some(2));
+    /// assert!(!voronoi.is_infinite(2));
+    /// assert!(voronoi.is_infinite(0) && voronoi.is_infinite(1) && voronoi.is_infinite(3));
+    /// assert_eq!(voronoi.largest_finite_area(), Some((2, 59)));
+    /// ```
+    pub fn is_infinite(&self, site: usize) -> bool {
+        self.infinite[site]
+    }
+
+    /// The site with the largest finite region and that region's area; see `largest_finite_area`.
+    pub fn largest_finite_area(&self) -> Option<(usize, usize)> {
+        largest_finite_area(&self.areas(), &self.infinite)
+    }
+}
+
+/// The site with the largest area that is not infinite, and that area. Ties go to the lowest
+/// site index.
+pub fn largest_finite_area<A: Ord + Copy>(areas: &[A], infinite: &[bool]) -> Option<(usize, A)> {
+    areas
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(site, _)| !infinite[*site])
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sites(coords: &[(i64, i64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point { x, y }).collect()
+    }
+
+    /// The puzzle's example, finite regions reaching outside the sites' bounding box under the
+    /// Euclidean and Chebyshev metrics, and a Euclidean region that skips rows on its way out.
+    fn cases() -> Vec<Vec<Point>> {
+        vec![
+            sites(&[(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]),
+            sites(&[(0, 0), (10, 0), (5, 1), (5, 10)]),
+            sites(&[(0, 0), (10, 0), (5, 3), (5, 10)]),
+            sites(&[(10, 1), (7, 9), (9, 4), (8, 2), (1, 5)]),
+        ]
+    }
+
+    /// The cells each site owns within `radius` of the origin along both axes.
+    fn brute_force_areas<M: Metric>(sites: &[Point], metric: &M, radius: i64) -> Vec<usize> {
+        let mut areas = vec![0; sites.len()];
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                if let Some(site) = closest_site(sites, metric, &Point { x, y }).owner() {
+                    areas[site] += 1;
+                }
+            }
+        }
+        areas
+    }
+
+    /// Whether each site owns a cell on the edge of the square `radius` from the origin.
+    fn brute_force_infinite<M: Metric>(sites: &[Point], metric: &M, radius: i64) -> Vec<bool> {
+        let mut infinite = vec![false; sites.len()];
+        for i in -radius..=radius {
+            for point in &[(i, -radius), (i, radius), (-radius, i), (radius, i)] {
+                let point = Point {
+                    x: point.0,
+                    y: point.1,
+                };
+                if let Some(site) = closest_site(sites, metric, &point).owner() {
+                    infinite[site] = true;
+                }
+            }
+        }
+        infinite
+    }
+
+    fn check_infinite<M: Metric>(metric: &M) {
+        for sites in cases() {
+            assert_eq!(
+                metric.infinite_regions(&sites),
+                brute_force_infinite(&sites, metric, 1000),
+                "{:?}",
+                sites
+            );
+        }
+    }
+
+    #[test]
+    fn infinite_regions_match_brute_force() {
+        check_infinite(&Manhattan);
+        check_infinite(&Euclidean);
+        check_infinite(&Chebyshev);
+    }
+
+    fn check_areas<M: Metric>(metric: &M, labellers: &[Labeller]) {
+        for sites in cases() {
+            let expected = brute_force_areas(&sites, metric, 200);
+            let infinite = metric.infinite_regions(&sites);
+            let finite = |areas: Vec<usize>| -> Vec<Option<usize>> {
+                areas
+                    .into_iter()
+                    .zip(infinite.iter())
+                    .map(|(area, infinite)| Some(area).filter(|_| !infinite))
+                    .collect()
+            };
+
+            for labeller in labellers {
+                let voronoi = Voronoi::with_labeller(sites.clone(), metric, *labeller).unwrap();
+                assert_eq!(
+                    finite(voronoi.areas()),
+                    finite(expected.clone()),
+                    "{:?} with {:?}",
+                    sites,
+                    labeller
+                );
+            }
+            let sparse = scanline::areas(&sites, metric).unwrap();
+            let sparse = sparse.into_iter().map(|area| area as usize).collect();
+            assert_eq!(finite(sparse), finite(expected), "{:?} sparse", sites);
+        }
+    }
+
+    #[test]
+    fn finite_areas_match_brute_force() {
+        let all = [Labeller::BruteForce, Labeller::Bfs, Labeller::KdTree];
+        check_areas(&Manhattan, &all);
+        check_areas(&Euclidean, &[Labeller::BruteForce, Labeller::KdTree]);
+        check_areas(&Chebyshev, &all);
+    }
+
+    #[test]
+    fn euclidean_region_skipping_rows_is_counted_whole() {
+        let sites = sites(&[(10, 1), (7, 9), (9, 4), (8, 2), (1, 5)]);
+
+        let voronoi = Voronoi::new(sites.clone(), &Euclidean).unwrap();
+        assert_eq!(voronoi.largest_finite_area(), Some((3, 207)));
+        assert_eq!(
+            voronoi.cell_at(&Point { x: -26, y: -68 }).unwrap().owner(),
+            Some(3)
+        );
+
+        let areas = scanline::areas(&sites, &Euclidean).unwrap();
+        let infinite = Euclidean.infinite_regions(&sites);
+        assert_eq!(largest_finite_area(&areas, &infinite), Some((3, 207)));
+    }
+
+    #[test]
+    fn chebyshev_region_outside_bounding_box_is_counted_whole() {
+        let sites = sites(&[(0, 0), (10, 0), (5, 3), (5, 10)]);
+
+        let voronoi = Voronoi::new(sites.clone(), &Chebyshev).unwrap();
+        assert_eq!(voronoi.largest_finite_area(), Some((2, 42)));
+        assert_eq!(
+            voronoi.cell_at(&Point { x: 5, y: -1 }).unwrap().owner(),
+            Some(2)
+        );
+
+        let areas = scanline::areas(&sites, &Chebyshev).unwrap();
+        let infinite = Chebyshev.infinite_regions(&sites);
+        assert_eq!(largest_finite_area(&areas, &infinite), Some((2, 42)));
     }
 }
@@ -0,0 +1,249 @@
+//! Points with any number of coordinates, for voxel data and beyond. Only the Manhattan metric
+//! is supported here: in any dimension, stepping from outside the sites' bounding box towards it
+//! brings every site exactly one closer, so a region is infinite exactly when it reaches the
+//! edge of the box.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use rayon::prelude::*;
+
+use crate::{axis_len, largest_finite_area, nearest, Cell, SpanError, MAX_CELLS};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PointN<const D: usize> {
+    pub coords: [i64; D],
+}
+
+impl<const D: usize> PointN<D> {
+    /// Parses `D` comma-separated coordinates.
+    pub fn from_line(line: &str) -> Self {
+        let mut coords = [0; D];
+        let mut split = line.split(',');
+        for coord in coords.iter_mut() {
+            *coord = split
+                .next()
+                .unwrap_or_else(|| panic!("Expected {} coordinates: {}", D, line))
+                .trim()
+                .parse()
+                .expect("Point format incorrect!");
+        }
+        assert!(
+            split.next().is_none(),
+            "Expected {} coordinates: {}",
+            D,
+            line
+        );
+
+        Self { coords }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> i128 {
+        self.coords
+            .iter()
+            .zip(other.coords.iter())
+            .map(|(a, b)| (i128::from(*b) - i128::from(*a)).abs())
+            .sum()
+    }
+}
+
+impl<const D: usize> fmt::Display for PointN<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, coord) in self.coords.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", coord)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// The number of coordinates on the first non-blank line of the input.
+pub fn dimension(input: &str) -> usize {
+    input
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map_or(0, |line| line.split(',').count())
+}
+
+/// Parses one point per line. Blank lines are skipped.
+pub fn parse_points<const D: usize>(input: &str) -> Vec<PointN<D>> {
+    input
+        .par_lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(PointN::from_line)
+        .collect()
+}
+
+/// The smallest box containing every point, as its lower and upper corners (both inclusive).
+pub fn bounding_box<const D: usize>(points: &[PointN<D>]) -> (PointN<D>, PointN<D>) {
+    let mut lower = PointN {
+        coords: [i64::MAX; D],
+    };
+    let mut upper = PointN {
+        coords: [i64::MIN; D],
+    };
+    for point in points {
+        for axis in 0..D {
+            lower.coords[axis] = lower.coords[axis].min(point.coords[axis]);
+            upper.coords[axis] = upper.coords[axis].max(point.coords[axis]);
+        }
+    }
+    (lower, upper)
+}
+
+/// A box of cells, numbered with the last axis changing fastest.
+#[derive(Copy, Clone, Debug)]
+pub struct Grid<const D: usize> {
+    pub origin: PointN<D>,
+    pub dims: [usize; D],
+}
+
+impl<const D: usize> Grid<D> {
+    /// The box from `lower` to `upper`, grown by `margin` cells on every side, if its cells can
+    /// all be numbered and there are at most `MAX_CELLS` of them.
+    pub(crate) fn around(
+        lower: PointN<D>,
+        upper: PointN<D>,
+        margin: i64,
+    ) -> Result<Self, SpanError> {
+        let mut grid = Grid {
+            origin: lower,
+            dims: [0; D],
+        };
+        for axis in 0..D {
+            grid.dims[axis] = axis_len(lower.coords[axis], upper.coords[axis], margin)?;
+            grid.origin.coords[axis] -= margin;
+        }
+        grid.dims
+            .iter()
+            .try_fold(1_usize, |len, dim| len.checked_mul(*dim))
+            .filter(|len| *len <= MAX_CELLS)
+            .ok_or(SpanError)?;
+        Ok(grid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn offsets(&self, mut index: usize) -> [usize; D] {
+        let mut offsets = [0; D];
+        for axis in (0..D).rev() {
+            offsets[axis] = index % self.dims[axis];
+            index /= self.dims[axis];
+        }
+        offsets
+    }
+
+    fn index(&self, offsets: &[usize; D]) -> usize {
+        offsets
+            .iter()
+            .zip(self.dims.iter())
+            .fold(0, |index, (offset, dim)| index * dim + offset)
+    }
+
+    pub fn point(&self, index: usize) -> PointN<D> {
+        let mut point = self.origin;
+        for (coord, offset) in point.coords.iter_mut().zip(self.offsets(index).iter()) {
+            *coord += *offset as i64;
+        }
+        point
+    }
+
+    /// The index of the cell at `point`, if it is inside the box.
+    pub fn index_of(&self, point: &PointN<D>) -> Option<usize> {
+        let mut offsets = [0; D];
+        for (axis, offset) in offsets.iter_mut().enumerate() {
+            let delta = i128::from(point.coords[axis]) - i128::from(self.origin.coords[axis]);
+            *offset = usize::try_from(delta)
+                .ok()
+                .filter(|offset| *offset < self.dims[axis])?;
+        }
+        Some(self.index(&offsets))
+    }
+
+    fn on_border(&self, index: usize) -> bool {
+        self.offsets(index)
+            .iter()
+            .zip(self.dims.iter())
+            .any(|(offset, dim)| *offset == 0 || *offset == dim - 1)
+    }
+
+    /// The cells one step away along a single axis.
+    pub(crate) fn neighbours(&self, index: usize) -> Vec<usize> {
+        let offsets = self.offsets(index);
+        let mut neighbours = vec![];
+        for axis in 0..D {
+            if offsets[axis] > 0 {
+                let mut neighbour = offsets;
+                neighbour[axis] -= 1;
+                neighbours.push(self.index(&neighbour));
+            }
+            if offsets[axis] + 1 < self.dims[axis] {
+                let mut neighbour = offsets;
+                neighbour[axis] += 1;
+                neighbours.push(self.index(&neighbour));
+            }
+        }
+        neighbours
+    }
+}
+
+/// The nearest site to every cell of the sites' bounding box, by Manhattan distance.
+pub struct VoronoiN<const D: usize> {
+    pub sites: Vec<PointN<D>>,
+    pub grid: Grid<D>,
+    pub cells: Vec<Cell>,
+}
+
+impl<const D: usize> VoronoiN<D> {
+    pub fn new(sites: Vec<PointN<D>>) -> Result<Self, SpanError> {
+        assert!(!sites.is_empty(), "Zero sites...");
+
+        let (lower, upper) = bounding_box(&sites);
+        let grid = Grid::around(lower, upper, 0)?;
+
+        let cells = (0..grid.len())
+            .into_par_iter()
+            .map(|i| {
+                let point = grid.point(i);
+                nearest(sites.iter().map(|p| p.manhattan(&point)))
+            })
+            .collect();
+
+        Ok(VoronoiN { sites, grid, cells })
+    }
+
+    /// The number of cells each site owns outright, by site index.
+    pub fn areas(&self) -> Vec<usize> {
+        let mut areas = vec![0; self.sites.len()];
+        for site in self.cells.iter().filter_map(Cell::owner) {
+            areas[site] += 1;
+        }
+        areas
+    }
+
+    /// Whether each site owns infinitely many cells: exactly those that reach the edge of the box.
+    pub fn infinite_regions(&self) -> Vec<bool> {
+        let mut infinite = vec![false; self.sites.len()];
+        for (i, cell) in self.cells.iter().enumerate() {
+            if let Some(site) = cell.owner() {
+                if self.grid.on_border(i) {
+                    infinite[site] = true;
+                }
+            }
+        }
+        infinite
+    }
+
+    pub fn largest_finite_area(&self) -> Option<(usize, usize)> {
+        largest_finite_area(&self.areas(), &self.infinite_regions())
+    }
+}
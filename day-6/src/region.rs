@@ -0,0 +1,307 @@
+use std::collections::VecDeque;
+
+use rayon::prelude::*;
+
+use crate::nd::{bounding_box, Grid, PointN};
+use crate::{axis_len, SpanError, MAX_CELLS};
+
+/// The cells whose total Manhattan distance to every site is less than a threshold, in any number
+/// of dimensions.
+pub struct SafeRegion<const D: usize> {
+    /// The region's bounding box, which is empty along with the region.
+    pub grid: Grid<D>,
+    pub cells: Vec<bool>,
+}
+
+/// The total distance from `position` to every coordinate in `coords`.
+fn axis_sum(coords: &[i64], position: i64) -> i128 {
+    coords
+        .iter()
+        .map(|c| (i128::from(*c) - i128::from(position)).abs())
+        .sum()
+}
+
+/// The positions along one axis whose total distance to the sorted `coords` is less than
+/// `budget`, if there are any. The total falls until the median coordinate and rises after it, so
+/// each end of the range is found with a binary search.
+fn axis_range(coords: &[i64], budget: i128) -> Option<(i64, i64)> {
+    let median = coords[coords.len() / 2];
+    if axis_sum(coords, median) >= budget {
+        return None;
+    }
+
+    let (mut lo, mut hi) = (i128::from(i64::MIN), i128::from(median));
+    while lo < hi {
+        let mid = (lo + hi).div_euclid(2);
+        if axis_sum(coords, mid as i64) < budget {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    let first = lo as i64;
+
+    let (mut lo, mut hi) = (i128::from(median), i128::from(i64::MAX));
+    while lo < hi {
+        let mid = (lo + hi + 1).div_euclid(2);
+        if axis_sum(coords, mid as i64) < budget {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Some((first, lo as i64))
+}
+
+/// The sites' coordinates along each axis, sorted.
+fn sorted_coords<const D: usize>(sites: &[PointN<D>]) -> Vec<Vec<i64>> {
+    (0..D)
+        .map(|axis| {
+            let mut coords: Vec<_> = sites.iter().map(|s| s.coords[axis]).collect();
+            coords.sort_unstable();
+            coords
+        })
+        .collect()
+}
+
+/// The range of positions the region covers along each axis, or `None` if the region is empty. A
+/// position belongs to the range when it fits under the threshold together with the smallest
+/// possible sums on the other axes, which makes the ranges the region's exact bounding box.
+fn axis_ranges(coords: &[Vec<i64>], threshold: i64) -> Option<Vec<(i64, i64)>> {
+    let smallest: Vec<i128> = coords
+        .iter()
+        .map(|coords| axis_sum(coords, coords[coords.len() / 2]))
+        .collect();
+    let spare = i128::from(threshold) - smallest.iter().sum::<i128>();
+
+    coords
+        .iter()
+        .zip(smallest.iter())
+        .map(|(coords, smallest)| axis_range(coords, smallest + spare))
+        .collect()
+}
+
+impl<const D: usize> SafeRegion<D> {
+    /// Searches the region's bounding box, which is found from the sites' coordinates alone.
+    pub fn new(sites: &[PointN<D>], threshold: i64) -> Result<Self, SpanError> {
+        assert!(!sites.is_empty(), "Zero sites...");
+
+        let grid = match axis_ranges(&sorted_coords(sites), threshold) {
+            Some(ranges) => {
+                let mut lower = sites[0];
+                let mut upper = sites[0];
+                for (axis, (first, last)) in ranges.into_iter().enumerate() {
+                    lower.coords[axis] = first;
+                    upper.coords[axis] = last;
+                }
+                Grid::around(lower, upper, 0)?
+            }
+            None => Grid {
+                origin: sites[0],
+                dims: [0; D],
+            },
+        };
+
+        let cells = (0..grid.len())
+            .into_par_iter()
+            .map(|i| {
+                let point = grid.point(i);
+                let total: i128 = sites.iter().map(|s| s.manhattan(&point)).sum();
+                total < i128::from(threshold)
+            })
+            .collect();
+
+        Ok(SafeRegion { grid, cells })
+    }
+
+    pub fn contains(&self, point: &PointN<D>) -> bool {
+        self.grid
+            .index_of(point)
+            .is_some_and(|index| self.cells[index])
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.iter().filter(|inside| **inside).count()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = PointN<D>> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, inside)| **inside)
+            .map(move |(i, _)| self.grid.point(i))
+    }
+
+    /// The smallest box containing the region, if it is not empty.
+    pub fn bounding_box(&self) -> Option<(PointN<D>, PointN<D>)> {
+        let points: Vec<_> = self.points().collect();
+        if points.is_empty() {
+            None
+        } else {
+            Some(bounding_box(&points))
+        }
+    }
+
+    /// Whether every cell can be reached from every other one axis-step at a time without
+    /// leaving the region. The empty region counts as connected.
+    pub fn is_connected(&self) -> bool {
+        let start = match self.cells.iter().position(|inside| *inside) {
+            Some(start) => start,
+            None => return true,
+        };
+
+        let mut seen = vec![false; self.cells.len()];
+        seen[start] = true;
+        let mut reached = 1;
+
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(index) = queue.pop_front() {
+            for neighbour in self.grid.neighbours(index) {
+                if self.cells[neighbour] && !seen[neighbour] {
+                    seen[neighbour] = true;
+                    reached += 1;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        reached == self.area()
+    }
+
+    /// The mean position of the region's cells, if it is not empty.
+    pub fn centroid(&self) -> Option<[f64; D]> {
+        let mut sum = [0.; D];
+        let mut count = 0;
+        for point in self.points() {
+            for (s, coord) in sum.iter_mut().zip(point.coords.iter()) {
+                *s += *coord as f64;
+            }
+            count += 1;
+        }
+
+        if count == 0 {
+            None
+        } else {
+            for s in sum.iter_mut() {
+                *s /= count as f64;
+            }
+            Some(sum)
+        }
+    }
+}
+
+/// The total distance from each position from `first` to `last` to every coordinate in the
+/// sorted `coords`.
+fn axis_sums(coords: &[i64], first: i64, last: i64) -> Vec<i128> {
+    let mut total = axis_sum(coords, first);
+    let mut below = coords.iter().take_while(|c| **c <= first).count();
+
+    let mut sums = vec![total];
+    for position in first + 1..=last {
+        // Stepping right moves away from every coordinate already passed and towards the rest.
+        total += below as i128 - (coords.len() - below) as i128;
+        while below < coords.len() && coords[below] <= position {
+            below += 1;
+        }
+        sums.push(total);
+    }
+    sums
+}
+
+/// The number of cells whose total Manhattan distance to every site is less than `threshold`.
+/// The distance splits into one part per axis, so rather than visit every cell this sums each
+/// axis once, over the region's bounding box only, and counts the combinations of sums that fit
+/// under the threshold.
+pub fn manhattan_area<const D: usize>(
+    sites: &[PointN<D>],
+    threshold: i64,
+) -> Result<usize, SpanError> {
+    assert!(!sites.is_empty(), "Zero sites...");
+
+    let coords = sorted_coords(sites);
+    let ranges = match axis_ranges(&coords, threshold) {
+        Some(ranges) => ranges,
+        None => return Ok(0),
+    };
+    for (first, last) in &ranges {
+        if axis_len(*first, *last, 0)? > MAX_CELLS {
+            return Err(SpanError);
+        }
+    }
+
+    let sums: Vec<Vec<i128>> = coords
+        .iter()
+        .zip(ranges.iter())
+        .map(|(coords, (first, last))| {
+            let mut sums = axis_sums(coords, *first, *last);
+            sums.par_sort_unstable();
+            sums
+        })
+        .collect();
+
+    Ok(count_under(&sums, i128::from(threshold)))
+}
+
+/// The number of ways to pick one sum from each axis with a total less than `budget`. Every
+/// axis's sums must be sorted. The last two axes are merged with two pointers: as the sum picked
+/// from the first of them grows, the number of sums from the second that still fit can only
+/// shrink, so both lists are walked once. A single axis is counted with a binary search.
+fn count_under(sums: &[Vec<i128>], budget: i128) -> usize {
+    match sums {
+        [] => (budget > 0) as usize,
+        [last] => last.partition_point(|sum| *sum < budget),
+        [first, last] => {
+            let mut fitting = last.len();
+            let mut count = 0;
+            for sum in first {
+                while fitting > 0 && sum + last[fitting - 1] >= budget {
+                    fitting -= 1;
+                }
+                if fitting == 0 {
+                    break;
+                }
+                count += fitting;
+            }
+            count
+        }
+        [first, rest @ ..] => first
+            .iter()
+            .take_while(|sum| **sum < budget)
+            .map(|sum| count_under(rest, budget - sum))
+            .sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sites(coords: &[[i64; 2]]) -> Vec<PointN<2>> {
+        coords.iter().map(|&coords| PointN { coords }).collect()
+    }
+
+    #[test]
+    fn counting_matches_the_searched_region() {
+        let sites = sites(&[[1, 1], [1, 6], [8, 3], [3, 4], [5, 5], [8, 9]]);
+        for threshold in &[0, 1, 30, 32, 100, 1000] {
+            let region = SafeRegion::new(&sites, *threshold).unwrap();
+            assert_eq!(manhattan_area(&sites, *threshold), Ok(region.area()));
+        }
+        assert_eq!(manhattan_area(&sites, 32), Ok(16));
+    }
+
+    #[test]
+    fn far_apart_sites_are_not_searched_in_between() {
+        let sites = sites(&[[0, 0], [1_000_000_000_000, 1]]);
+        assert_eq!(manhattan_area(&sites, 10_000), Ok(0));
+        assert_eq!(SafeRegion::new(&sites, 10_000).unwrap().area(), 0);
+    }
+
+    #[test]
+    fn regions_too_large_to_hold_are_refused() {
+        let sites = sites(&[[0, 0]]);
+        assert_eq!(manhattan_area(&sites, i64::MAX), Err(SpanError));
+        assert!(SafeRegion::new(&sites, i64::MAX).is_err());
+    }
+}
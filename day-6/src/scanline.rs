@@ -0,0 +1,148 @@
+//! Region areas computed one row at a time, for sites too far apart to hold their bounding box
+//! in memory.
+//!
+//! Along a row, the cells where one site is strictly closer than another form a prefix of the
+//! row if the site is further left, a suffix if it is further right, and an interval centred on
+//! the site if they share a column. This holds for every metric here, so each site owns a single
+//! run of cells in each row, found by narrowing the whole row against every other site in turn.
+
+use std::ops::RangeInclusive;
+
+use rayon::prelude::*;
+
+use crate::{bounding_box, check_span, grow, Metric, Point, SpanError};
+
+/// The cell halfway between `lo` and `hi`, rounding down, or up if `round_up`. Worked out in
+/// `i128`, as the gap between two `i64` cells may not fit in an `i64`.
+fn midpoint(lo: i64, hi: i64, round_up: bool) -> i64 {
+    let sum = i128::from(lo) + i128::from(hi) + i128::from(round_up);
+    sum.div_euclid(2) as i64
+}
+
+/// The first cell in `lo..=hi` for which `wins` holds, given that it holds on a suffix.
+fn first_winning<F: Fn(i64) -> bool>(lo: i64, hi: i64, wins: F) -> Option<i64> {
+    if !wins(hi) {
+        return None;
+    }
+    let (mut lo, mut hi) = (lo, hi);
+    while lo < hi {
+        let mid = midpoint(lo, hi, false);
+        if wins(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Some(lo)
+}
+
+/// The last cell in `lo..=hi` for which `wins` holds, given that it holds on a prefix.
+fn last_winning<F: Fn(i64) -> bool>(lo: i64, hi: i64, wins: F) -> Option<i64> {
+    if !wins(lo) {
+        return None;
+    }
+    let (mut lo, mut hi) = (lo, hi);
+    while lo < hi {
+        let mid = midpoint(lo, hi, true);
+        if wins(mid) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Some(lo)
+}
+
+/// The cells of row `y` between `from` and `to` that `site` owns outright, if any.
+pub fn owned_run<M: Metric>(
+    sites: &[Point],
+    metric: &M,
+    site: usize,
+    y: i64,
+    from: i64,
+    to: i64,
+) -> Option<RangeInclusive<i64>> {
+    let s = sites[site];
+    let (mut lo, mut hi) = (from, to);
+
+    for (j, other) in sites.iter().enumerate() {
+        if j == site {
+            continue;
+        }
+
+        let wins = |x| {
+            let cell = Point { x, y };
+            metric.distance(&s, &cell) < metric.distance(other, &cell)
+        };
+
+        if s.x < other.x {
+            hi = last_winning(lo, hi, wins)?;
+        } else if s.x > other.x {
+            lo = first_winning(lo, hi, wins)?;
+        } else if s.x < lo {
+            hi = last_winning(lo, hi, wins)?;
+        } else if s.x > hi {
+            lo = first_winning(lo, hi, wins)?;
+        } else {
+            lo = first_winning(lo, s.x, wins)?;
+            hi = last_winning(s.x, hi, wins)?;
+        }
+    }
+
+    Some(lo..=hi)
+}
+
+/// The number of cells in a run.
+fn len(run: &RangeInclusive<i64>) -> u128 {
+    (i128::from(*run.end()) - i128::from(*run.start()) + 1) as u128
+}
+
+/// The number of cells each site owns outright, by site index, over a box grown from
+/// `Metric::finite_extent` until it holds every finite region whole. Agrees with `Voronoi::areas` without ever holding
+/// more than a row's worth of runs. A row can be wider than a `u64` can count, so areas are
+/// `u128`s.
+pub fn areas<M: Metric>(sites: &[Point], metric: &M) -> Result<Vec<u128>, SpanError> {
+    assert!(!sites.is_empty(), "Zero sites...");
+
+    let (lower, upper) = bounding_box(sites);
+    check_span(metric, &lower, &upper)?;
+    let infinite = metric.infinite_regions(sites);
+    let (lower, upper) = metric.finite_extent(sites, &infinite);
+
+    grow(lower, upper, |from, to| {
+        check_span(metric, &from, &to)?;
+
+        Ok((from.y..=to.y)
+            .into_par_iter()
+            .map(|y| {
+                let runs: Vec<_> = (0..sites.len())
+                    .map(|site| owned_run(sites, metric, site, y, from.x, to.x))
+                    .collect();
+
+                let mut reached = [false; 4];
+                for (site, run) in runs.iter().enumerate() {
+                    if let Some(run) = run.as_ref().filter(|_| !infinite[site]) {
+                        reached[0] |= *run.start() == from.x;
+                        reached[1] |= *run.end() == to.x;
+                        reached[2] |= y == from.y;
+                        reached[3] |= y == to.y;
+                    }
+                }
+
+                let areas: Vec<_> = runs.iter().map(|run| run.as_ref().map_or(0, len)).collect();
+                (areas, reached)
+            })
+            .reduce(
+                || (vec![0; sites.len()], [false; 4]),
+                |(mut a, mut a_reached), (b, b_reached)| {
+                    for (a, b) in a.iter_mut().zip(b) {
+                        *a += b;
+                    }
+                    for (a, b) in a_reached.iter_mut().zip(b_reached.iter()) {
+                        *a |= *b;
+                    }
+                    (a, a_reached)
+                },
+            ))
+    })
+}
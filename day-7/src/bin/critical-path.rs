@@ -0,0 +1,111 @@
+use std::io::{prelude::*, BufReader};
+
+use day_7::critical::CriticalPath;
+use day_7::policy::{policy, POLICY_NAMES};
+use day_7::schedule::schedule;
+use day_7::{load_durations, load_task_graph};
+
+fn main() {
+    let matches = clap::App::new("critical-path")
+        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("workers")
+                .long("workers")
+                .takes_value(true)
+                .default_value("5")
+                .help("How many tasks can be worked on at once in the schedule to compare against"),
+        )
+        .arg(
+            clap::Arg::with_name("base-duration")
+                .long("base-duration")
+                .takes_value(true)
+                .default_value("60")
+                .help("Seconds every task takes, before the extra second per letter for single-letter names"),
+        )
+        .arg(
+            clap::Arg::with_name("durations")
+                .long("durations")
+                .takes_value(true)
+                .help("File of `name seconds` lines overriding the duration of individual tasks"),
+        )
+        .arg(
+            clap::Arg::with_name("policy")
+                .long("policy")
+                .takes_value(true)
+                .possible_values(POLICY_NAMES)
+                .default_value("alphabetical")
+                .help("Which ready task to start first"),
+        )
+        .get_matches();
+
+    let workers: usize = matches
+        .value_of("workers")
+        .unwrap()
+        .parse()
+        .expect("--workers must be a positive integer");
+    assert!(workers > 0, "--workers must be a positive integer");
+
+    let base_duration: u64 = matches
+        .value_of("base-duration")
+        .unwrap()
+        .parse()
+        .expect("--base-duration must be a non-negative integer");
+
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
+        Some(input_file) => BufReader::new(Box::new(
+            std::fs::File::open(input_file).expect("Could not open input file"),
+        )),
+        None => BufReader::new(Box::new(std::io::stdin())),
+    };
+
+    let mut input_deck = String::new();
+    reader.read_to_string(&mut input_deck).unwrap();
+
+    let graph = load_task_graph(&input_deck);
+    let durations = load_durations(&graph, base_duration, matches.value_of("durations"));
+
+    let critical = CriticalPath::new(&graph.tasks, &durations);
+
+    let name_width = (0..graph.tasks.len())
+        .map(|task| graph.names.name(task).len())
+        .chain(Some("Task".len()))
+        .max()
+        .unwrap();
+    println!(
+        "{:<5$}  {:>8}  {:>8}  {:>8}  {:>8}",
+        "Task", "Duration", "Earliest", "Latest", "Slack", name_width
+    );
+    for (task, timing) in critical.timings.iter().enumerate() {
+        println!(
+            "{:<5$}  {:>8}  {:>8}  {:>8}  {:>8}",
+            graph.names.name(task),
+            timing.duration,
+            timing.earliest_start,
+            timing.latest_start,
+            timing.slack(),
+            name_width
+        );
+    }
+    println!();
+
+    let chain: Vec<_> = critical
+        .chain
+        .iter()
+        .map(|task| graph.names.name(*task))
+        .collect();
+    println!("Critical chain: {}", chain.join(" -> "));
+    println!("Shortest possible time: {}", critical.length);
+
+    let policy = policy(
+        matches.value_of("policy").unwrap(),
+        &graph.tasks,
+        &durations,
+    );
+    let total_time = schedule(&graph.tasks, &durations, workers, &*policy).total_time();
+    println!(
+        "With {} workers: {} ({} above the shortest possible time)",
+        workers,
+        total_time,
+        total_time - critical.length
+    );
+}
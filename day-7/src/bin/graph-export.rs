@@ -0,0 +1,212 @@
+use std::io::{prelude::*, BufReader, BufWriter};
+
+use day_7::policy::{policy, POLICY_NAMES};
+use day_7::reduction::reduced_dependents;
+use day_7::schedule::{order, schedule, Slot};
+use day_7::{load_durations, load_task_graph, TaskGraph};
+
+/// Extra lines to show under each task's name.
+struct Annotations {
+    /// Each task's 1-based position in the completion order.
+    positions: Option<Vec<usize>>,
+    /// Each task's run in the parallel schedule.
+    slots: Option<Vec<Slot>>,
+}
+
+impl Annotations {
+    fn lines(&self, graph: &TaskGraph, task: usize) -> Vec<String> {
+        let mut lines = vec![graph.names.name(task).to_owned()];
+        if let Some(positions) = &self.positions {
+            lines.push(format!("#{}", positions[task]));
+        }
+        if let Some(slots) = &self.slots {
+            lines.push(format!("{}-{}", slots[task].start, slots[task].end));
+        }
+        lines
+    }
+}
+
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_dot<W: Write>(
+    writer: &mut W,
+    graph: &TaskGraph,
+    dependents: &[Vec<usize>],
+    annotations: &Annotations,
+) -> std::io::Result<()> {
+    writeln!(writer, "digraph tasks {{")?;
+    writeln!(writer, "    node [shape=box];")?;
+    for task in 0..graph.tasks.len() {
+        let label: Vec<_> = annotations
+            .lines(graph, task)
+            .iter()
+            .map(|line| line.replace('\\', "\\\\").replace('"', "\\\""))
+            .collect();
+        writeln!(
+            writer,
+            "    {} [label=\"{}\"];",
+            dot_string(graph.names.name(task)),
+            label.join("\\n")
+        )?;
+    }
+    for (task, dependents) in dependents.iter().enumerate() {
+        for dependent in dependents {
+            writeln!(
+                writer,
+                "    {} -> {};",
+                dot_string(graph.names.name(task)),
+                dot_string(graph.names.name(*dependent))
+            )?;
+        }
+    }
+    writeln!(writer, "}}")
+}
+
+// Mermaid node ids can't hold arbitrary names, so nodes are numbered and labelled with the name.
+fn write_mermaid<W: Write>(
+    writer: &mut W,
+    graph: &TaskGraph,
+    dependents: &[Vec<usize>],
+    annotations: &Annotations,
+) -> std::io::Result<()> {
+    writeln!(writer, "flowchart TD")?;
+    for task in 0..graph.tasks.len() {
+        let label: Vec<_> = annotations
+            .lines(graph, task)
+            .iter()
+            .map(|line| line.replace('"', "#quot;"))
+            .collect();
+        writeln!(writer, "    t{}[\"{}\"]", task, label.join("<br/>"))?;
+    }
+    for (task, dependents) in dependents.iter().enumerate() {
+        for dependent in dependents {
+            writeln!(writer, "    t{} --> t{}", task, dependent)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let matches = clap::App::new("graph-export")
+        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["dot", "mermaid"])
+                .default_value("dot")
+                .help("Graphviz DOT or a Mermaid flowchart"),
+        )
+        .arg(
+            clap::Arg::with_name("reduce")
+                .long("reduce")
+                .help("Hide dependencies already implied by other dependencies"),
+        )
+        .arg(
+            clap::Arg::with_name("order")
+                .long("order")
+                .help("Label each task with its position in the task-graph completion order"),
+        )
+        .arg(
+            clap::Arg::with_name("times")
+                .long("times")
+                .help("Label each task with its start and end time in the parallel-work schedule"),
+        )
+        .arg(
+            clap::Arg::with_name("workers")
+                .long("workers")
+                .takes_value(true)
+                .default_value("5")
+                .help("How many tasks can be worked on at once, for --times"),
+        )
+        .arg(
+            clap::Arg::with_name("policy")
+                .long("policy")
+                .takes_value(true)
+                .possible_values(POLICY_NAMES)
+                .default_value("alphabetical")
+                .help("Which ready task to start first, for --order and --times"),
+        )
+        .arg(
+            clap::Arg::with_name("base-duration")
+                .long("base-duration")
+                .takes_value(true)
+                .default_value("60")
+                .help("Seconds every task takes, before the extra second per letter for single-letter names"),
+        )
+        .arg(
+            clap::Arg::with_name("durations")
+                .long("durations")
+                .takes_value(true)
+                .help("File of `name seconds` lines overriding the duration of individual tasks"),
+        )
+        .get_matches();
+
+    let workers: usize = matches
+        .value_of("workers")
+        .unwrap()
+        .parse()
+        .expect("--workers must be a positive integer");
+    assert!(workers > 0, "--workers must be a positive integer");
+
+    let base_duration: u64 = matches
+        .value_of("base-duration")
+        .unwrap()
+        .parse()
+        .expect("--base-duration must be a non-negative integer");
+
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
+        Some(input_file) => BufReader::new(Box::new(
+            std::fs::File::open(input_file).expect("Could not open input file"),
+        )),
+        None => BufReader::new(Box::new(std::io::stdin())),
+    };
+
+    let mut input_deck = String::new();
+    reader.read_to_string(&mut input_deck).unwrap();
+
+    let graph = load_task_graph(&input_deck);
+    let durations = load_durations(&graph, base_duration, matches.value_of("durations"));
+    let policy = policy(
+        matches.value_of("policy").unwrap(),
+        &graph.tasks,
+        &durations,
+    );
+
+    let completion_order = order(&graph.tasks, &*policy);
+
+    let annotations = Annotations {
+        positions: if matches.is_present("order") {
+            let mut positions = vec![0; graph.tasks.len()];
+            for (i, task) in completion_order.iter().enumerate() {
+                positions[*task] = i + 1;
+            }
+            Some(positions)
+        } else {
+            None
+        },
+        slots: if matches.is_present("times") {
+            let mut slots = schedule(&graph.tasks, &durations, workers, &*policy).slots;
+            slots.sort_by_key(|slot| slot.task);
+            Some(slots)
+        } else {
+            None
+        },
+    };
+
+    let dependents = if matches.is_present("reduce") {
+        reduced_dependents(&graph.tasks, &completion_order)
+    } else {
+        graph.tasks.iter().map(|t| t.dependents.clone()).collect()
+    };
+
+    let stdout = std::io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    match matches.value_of("format").unwrap() {
+        "mermaid" => write_mermaid(&mut writer, &graph, &dependents, &annotations),
+        _ => write_dot(&mut writer, &graph, &dependents, &annotations),
+    }
+    .expect("Could not write graph");
+}
@@ -1,126 +1,285 @@
-use std::io::{prelude::*, BufReader};
+use std::cmp::max;
+use std::io::{prelude::*, BufReader, BufWriter};
 
-use day_7::{Task, TaskDependency};
-use rayon::prelude::*;
+use serde::Serialize;
 
-fn main() {
-    let matches = clap::App::new("parallel-work")
-        .arg(clap::Arg::with_name("input").required(false))
-        .get_matches();
+use day_7::policy::{policy, POLICY_NAMES};
+use day_7::schedule::{schedule, Schedule};
+use day_7::{load_durations, load_task_graph, TaskGraph};
 
-    let mut reader: BufReader<Box<Read>> = match matches.value_of("input") {
-        Some(input_file) => BufReader::new(Box::new(
-            std::fs::File::open(input_file).expect("Could not open input file"),
-        )),
-        None => BufReader::new(Box::new(std::io::stdin())),
-    };
+// SVG layout, in pixels
+const CHART_WIDTH: f64 = 1000.;
+const ROW_HEIGHT: f64 = 30.;
+const LABEL_WIDTH: f64 = 80.;
+const AXIS_HEIGHT: f64 = 30.;
 
-    let mut input_deck = String::new();
-    reader.read_to_string(&mut input_deck).unwrap();
+/// Writes the puzzle's second-by-second listing: what each worker is doing, and which tasks are
+/// done, at the start of every second.
+fn write_table<W: Write>(
+    writer: &mut W,
+    graph: &TaskGraph,
+    schedule: &Schedule,
+) -> std::io::Result<()> {
+    let width = (0..graph.tasks.len())
+        .map(|task| graph.names.name(task).len())
+        .chain((1..=schedule.workers).map(|worker| format!("Worker {}", worker).len()))
+        .max()
+        .unwrap_or(0);
+    let second_width = max("Second".len(), schedule.total_time().to_string().len());
 
-    let dependencies = input_deck
-        .as_str()
-        .par_lines()
-        .map(TaskDependency::from_line)
-        .collect::<Vec<_>>();
+    write!(writer, "{:>1$}", "Second", second_width)?;
+    for worker in 1..=schedule.workers {
+        write!(writer, "   {:^1$}", format!("Worker {}", worker), width)?;
+    }
+    writeln!(writer, "   Done")?;
 
-    let mut tasks = vec![Task::default(); 26];
+    let mut by_end = schedule.slots.clone();
+    by_end.sort_by_key(|slot| (slot.end, slot.task));
 
-    for dep in &dependencies {
-        tasks[dep.target as usize].dependencies.push(dep.dependency);
-        tasks[dep.dependency as usize].dependents.push(dep.target);
+    let mut done = vec![];
+    let mut finished = by_end.iter().peekable();
+    for time in 0..=schedule.total_time() {
+        while let Some(slot) = finished.next_if(|slot| slot.end <= time) {
+            done.push(slot.task);
+        }
 
-        tasks[dep.target as usize].remaining_time = 60u8 + dep.target + 1;
-        tasks[dep.dependency as usize].remaining_time = 60u8 + dep.dependency + 1;
+        write!(writer, "{:>1$}", time, second_width)?;
+        for worker in 0..schedule.workers {
+            let task = schedule
+                .running(worker, time)
+                .map_or(".", |task| graph.names.name(task));
+            write!(writer, "   {:^1$}", task, width)?;
+        }
+        if done.is_empty() {
+            writeln!(writer)?;
+        } else {
+            writeln!(writer, "   {}", graph.format_order(&done))?;
+        }
     }
 
-    // sort and dedup dependencies and dependents in task list
-    for task in &mut tasks {
-        task.dependencies.sort();
-        task.dependencies.dedup();
+    Ok(())
+}
 
-        task.dependents.sort();
-        task.dependents.dedup();
-    }
+/// The schedule as JSON, with task names listed by task id.
+#[derive(Serialize)]
+struct ScheduleReport<'a> {
+    tasks: Vec<&'a str>,
+    total_time: u64,
+    #[serde(flatten)]
+    schedule: &'a Schedule,
+}
 
-    let mut num_completed = tasks.iter().filter(|t| t.remaining_time == 0).count();
+fn write_json<W: Write>(
+    writer: &mut W,
+    graph: &TaskGraph,
+    schedule: &Schedule,
+) -> std::io::Result<()> {
+    let report = ScheduleReport {
+        tasks: (0..graph.tasks.len())
+            .map(|task| graph.names.name(task))
+            .collect(),
+        total_time: schedule.total_time(),
+        schedule,
+    };
+    serde_json::to_writer_pretty(&mut *writer, &report)?;
+    writeln!(writer)
+}
 
-    let mut completion_time: u32 = 0;
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
-    let mut in_progress = vec![];
-    let mut in_progress_removals = vec![];
-    let mut completed = vec![];
-    let mut removals = vec![];
+/// The spacing of time axis ticks: a 1, 2 or 5 times a power of ten giving at most ten ticks.
+fn tick_spacing(total_time: u64) -> u64 {
+    let mut spacing = 1;
+    loop {
+        for &step in &[spacing, spacing * 2, spacing * 5] {
+            if total_time / step <= 10 {
+                return step;
+            }
+        }
+        spacing *= 10;
+    }
+}
 
-    while num_completed != 26 {
-        in_progress_removals.clear();
-        completed.clear();
-        removals.clear();
+/// Writes a Gantt chart with a row per worker and a bar per task, scaled to a fixed width.
+fn write_svg<W: Write>(
+    writer: &mut W,
+    graph: &TaskGraph,
+    schedule: &Schedule,
+) -> std::io::Result<()> {
+    let total_time = schedule.total_time();
+    let scale = CHART_WIDTH / max(total_time, 1) as f64;
+    let height = schedule.workers as f64 * ROW_HEIGHT + AXIS_HEIGHT;
 
-        let start_in_progress_length = in_progress.len();
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="sans-serif" font-size="12">"#,
+        LABEL_WIDTH + CHART_WIDTH + 20.,
+        height
+    )?;
 
-        // Mark prog
-        let new_in_progress = tasks
-            .iter_mut()
-            .enumerate()
-            .filter(|(_, t)| !t.in_progress)
-            .filter(|(_, t)| t.remaining_time > 0)
-            .filter(|(_, t)| t.dependencies.is_empty())
-            .map(|(i, _)| i)
-            .take(5 - start_in_progress_length);
+    for worker in 0..schedule.workers {
+        writeln!(
+            writer,
+            r#"<text x="4" y="{}" dominant-baseline="middle">Worker {}</text>"#,
+            (worker as f64 + 0.5) * ROW_HEIGHT,
+            worker + 1
+        )?;
+    }
 
-        // Get some more work to do
-        in_progress.extend(new_in_progress);
+    for slot in &schedule.slots {
+        let x = LABEL_WIDTH + slot.start as f64 * scale;
+        let y = slot.worker as f64 * ROW_HEIGHT;
+        let width = (slot.end - slot.start) as f64 * scale;
+        let name = xml_escape(graph.names.name(slot.task));
+        writeln!(
+            writer,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="steelblue" stroke="white"><title>{} ({}-{})</title></rect>"#,
+            x,
+            y + 2.,
+            width,
+            ROW_HEIGHT - 4.,
+            name,
+            slot.start,
+            slot.end
+        )?;
+        writeln!(
+            writer,
+            r#"<text x="{}" y="{}" fill="white" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+            x + width / 2.,
+            y + ROW_HEIGHT / 2.,
+            name
+        )?;
+    }
 
-        // Mark new tasks as in_progress
-        for task_idx in &in_progress[start_in_progress_length..] {
-            tasks[*task_idx as usize].in_progress = true;
-        }
+    let axis_y = schedule.workers as f64 * ROW_HEIGHT;
+    writeln!(
+        writer,
+        r#"<line x1="{0}" y1="{1}" x2="{2}" y2="{1}" stroke="black"/>"#,
+        LABEL_WIDTH,
+        axis_y,
+        LABEL_WIDTH + CHART_WIDTH
+    )?;
+    let spacing = tick_spacing(total_time);
+    for tick in (0..=total_time).step_by(spacing as usize) {
+        let x = LABEL_WIDTH + tick as f64 * scale;
+        writeln!(
+            writer,
+            r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="black"/><text x="{0}" y="{3}" text-anchor="middle">{4}</text>"#,
+            x,
+            axis_y,
+            axis_y + 5.,
+            axis_y + 18.,
+            tick
+        )?;
+    }
 
-        assert!(!in_progress.is_empty());
+    writeln!(writer, "</svg>")
+}
 
-        // figure out which task is closest to being completed
-        let time_to_run = in_progress
-            .iter()
-            .cloned()
-            .map(|task_idx| tasks[task_idx].remaining_time)
-            .min()
-            .unwrap();
+fn main() {
+    let matches = clap::App::new("parallel-work")
+        .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("workers")
+                .long("workers")
+                .takes_value(true)
+                .default_value("5")
+                .help("How many tasks can be worked on at once"),
+        )
+        .arg(
+            clap::Arg::with_name("base-duration")
+                .long("base-duration")
+                .takes_value(true)
+                .default_value("60")
+                .help("Seconds every task takes, before the extra second per letter for single-letter names"),
+        )
+        .arg(
+            clap::Arg::with_name("durations")
+                .long("durations")
+                .takes_value(true)
+                .help("File of `name seconds` lines overriding the duration of individual tasks"),
+        )
+        .arg(
+            clap::Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["total", "table", "json", "svg"])
+                .default_value("total")
+                .help("Print just the total time, or the whole schedule as a table, JSON or an SVG Gantt chart"),
+        )
+        .arg(
+            clap::Arg::with_name("policy")
+                .long("policy")
+                .takes_value(true)
+                .possible_values(POLICY_NAMES)
+                .default_value("alphabetical")
+                .help("Which ready task to start first"),
+        )
+        .arg(
+            clap::Arg::with_name("compare")
+                .long("compare")
+                .conflicts_with_all(&["policy", "format"])
+                .help("Print the total time under every policy"),
+        )
+        .get_matches();
 
-        completion_time += time_to_run as u32;
+    let workers: usize = matches
+        .value_of("workers")
+        .unwrap()
+        .parse()
+        .expect("--workers must be a positive integer");
+    assert!(workers > 0, "--workers must be a positive integer");
 
-        // decrease remaining_time on all in-progress tasks and track completed
-        for (i, task_idx) in in_progress.iter().enumerate() {
-            tasks[*task_idx as usize].remaining_time -= time_to_run;
+    let base_duration: u64 = matches
+        .value_of("base-duration")
+        .unwrap()
+        .parse()
+        .expect("--base-duration must be a non-negative integer");
 
-            if tasks[*task_idx as usize].remaining_time == 0 {
-                completed.push(*task_idx);
-                in_progress_removals.push(i);
-            }
-        }
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
+        Some(input_file) => BufReader::new(Box::new(
+            std::fs::File::open(input_file).expect("Could not open input file"),
+        )),
+        None => BufReader::new(Box::new(std::io::stdin())),
+    };
 
-        // Remove from in_progress in reverse
-        for to_remove in in_progress_removals.iter().rev() {
-            in_progress.remove(*to_remove);
-        }
+    let mut input_deck = String::new();
+    reader.read_to_string(&mut input_deck).unwrap();
 
-        num_completed += completed.len();
+    let graph = load_task_graph(&input_deck);
 
-        // Remove completed tasks
-        for task_idx in &completed {
-            for dependent in &tasks[*task_idx as usize].dependents {
-                let remove_at = tasks[*dependent as usize]
-                    .dependencies
-                    .binary_search(&(*task_idx as u8))
-                    .expect("Task dependency wasn't recorded");
-                removals.push((*dependent, remove_at));
-            }
-        }
+    let durations = load_durations(&graph, base_duration, matches.value_of("durations"));
 
-        for removal in &removals {
-            tasks[removal.0 as usize].dependencies.remove(removal.1);
+    if matches.is_present("compare") {
+        let width = POLICY_NAMES.iter().map(|name| name.len()).max().unwrap();
+        println!("{:<1$}  Total time", "Policy", width);
+        for name in POLICY_NAMES {
+            let policy = policy(name, &graph.tasks, &durations);
+            let total_time = schedule(&graph.tasks, &durations, workers, &*policy).total_time();
+            println!("{:<2$}  {:>10}", name, total_time, width);
         }
+        return;
     }
 
-    println!("{}", completion_time);
+    let policy = policy(
+        matches.value_of("policy").unwrap(),
+        &graph.tasks,
+        &durations,
+    );
+    let schedule = schedule(&graph.tasks, &durations, workers, &*policy);
+
+    let stdout = std::io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    match matches.value_of("format").unwrap() {
+        "table" => write_table(&mut writer, &graph, &schedule),
+        "json" => write_json(&mut writer, &graph, &schedule),
+        "svg" => write_svg(&mut writer, &graph, &schedule),
+        _ => writeln!(writer, "{}", schedule.total_time()),
+    }
+    .expect("Could not write schedule");
 }
@@ -1,14 +1,42 @@
 use std::io::{prelude::*, BufReader};
 
-use day_7::{Task, TaskDependency};
-use rayon::prelude::*;
+use day_7::policy::{policy, POLICY_NAMES};
+use day_7::schedule::order;
+use day_7::{load_durations, load_task_graph};
 
 fn main() {
     let matches = clap::App::new("task-graph")
         .arg(clap::Arg::with_name("input").required(false))
+        .arg(
+            clap::Arg::with_name("policy")
+                .long("policy")
+                .takes_value(true)
+                .possible_values(POLICY_NAMES)
+                .default_value("alphabetical")
+                .help("Which ready task to start first"),
+        )
+        .arg(
+            clap::Arg::with_name("base-duration")
+                .long("base-duration")
+                .takes_value(true)
+                .default_value("60")
+                .help("Seconds every task takes, before the extra second per letter for single-letter names"),
+        )
+        .arg(
+            clap::Arg::with_name("durations")
+                .long("durations")
+                .takes_value(true)
+                .help("File of `name seconds` lines overriding the duration of individual tasks"),
+        )
         .get_matches();
 
-    let mut reader: BufReader<Box<Read>> = match matches.value_of("input") {
+    let base_duration: u64 = matches
+        .value_of("base-duration")
+        .unwrap()
+        .parse()
+        .expect("--base-duration must be a non-negative integer");
+
+    let mut reader: BufReader<Box<dyn Read>> = match matches.value_of("input") {
         Some(input_file) => BufReader::new(Box::new(
             std::fs::File::open(input_file).expect("Could not open input file"),
         )),
@@ -18,62 +46,14 @@ fn main() {
     let mut input_deck = String::new();
     reader.read_to_string(&mut input_deck).unwrap();
 
-    let dependencies = input_deck
-        .as_str()
-        .par_lines()
-        .map(TaskDependency::from_line)
-        .collect::<Vec<_>>();
-
-    let mut tasks = vec![Task::default(); 26];
-    for dep in &dependencies {
-        tasks[dep.target as usize].dependencies.push(dep.dependency);
-        tasks[dep.dependency as usize].dependents.push(dep.target);
-
-        tasks[dep.target as usize].incomplete = true;
-        tasks[dep.dependency as usize].incomplete = true;
-    }
-
-    // sort and dedup dependencies and dependents in task list
-    for task in &mut tasks {
-        task.dependencies.sort();
-        task.dependencies.dedup();
-
-        task.dependents.sort();
-        task.dependents.dedup();
-    }
-
-    let mut num_completed = tasks.iter().filter(|t| !t.incomplete).count();
-    let mut completion_order = String::new();
-
-    let mut removals = vec![];
-    while num_completed != 26 {
-        let task_idx = tasks
-            .iter()
-            .enumerate()
-            .filter(|(_, t)| t.incomplete)
-            .find(|(_, t)| t.dependencies.is_empty())
-            .map(|(i, _)| i)
-            .expect("Exhausted task list too quickly") as u8;
-
-        removals.clear();
-        for dependent in &tasks[task_idx as usize].dependents {
-            let remove_at = tasks[*dependent as usize]
-                .dependencies
-                .binary_search(&task_idx)
-                .expect("Task dependency wasn't recorded");
-            removals.push((*dependent, remove_at));
-        }
-
-        for removal in &removals {
-            tasks[removal.0 as usize].dependencies.remove(removal.1);
-        }
-
-        tasks[task_idx as usize].incomplete = false;
-
-        completion_order += unsafe { String::from_utf8_unchecked(vec![b'A' + task_idx]) }.as_str();
-
-        num_completed += 1;
-    }
+    let graph = load_task_graph(&input_deck);
+    let durations = load_durations(&graph, base_duration, matches.value_of("durations"));
+    let policy = policy(
+        matches.value_of("policy").unwrap(),
+        &graph.tasks,
+        &durations,
+    );
+    let completion_order = order(&graph.tasks, &*policy);
 
-    println!("{}", completion_order);
+    println!("{}", graph.format_order(&completion_order));
 }
@@ -0,0 +1,124 @@
+//! Critical path analysis: how early and how late each task can run with as many workers as
+//! there are tasks, which bounds the completion time of any schedule from below.
+
+use std::collections::BTreeSet;
+
+use crate::Task;
+
+/// When a task can start with unlimited workers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Timing {
+    pub duration: u64,
+    /// The earliest it can start, once all of its dependencies are done.
+    pub earliest_start: u64,
+    /// The latest it can start without delaying the whole graph.
+    pub latest_start: u64,
+}
+
+impl Timing {
+    pub fn earliest_finish(&self) -> u64 {
+        self.earliest_start + self.duration
+    }
+
+    pub fn latest_finish(&self) -> u64 {
+        self.latest_start + self.duration
+    }
+
+    /// How long the task can be delayed without delaying the whole graph.
+    pub fn slack(&self) -> u64 {
+        self.latest_start - self.earliest_start
+    }
+}
+
+pub struct CriticalPath {
+    /// Timings by task id.
+    pub timings: Vec<Timing>,
+    /// The shortest possible completion time.
+    pub length: u64,
+    /// A longest chain of dependent tasks, each starting as soon as the one before finishes.
+    pub chain: Vec<usize>,
+}
+
+/// Every task in an order where each comes after its dependencies, lowest ids first where there is
+/// a choice. Tasks must not depend on each other in a cycle.
+fn topological_order(tasks: &[Task]) -> Vec<usize> {
+    let mut waiting: Vec<_> = tasks.iter().map(|t| t.dependencies.len()).collect();
+    let mut ready: BTreeSet<_> = (0..tasks.len()).filter(|t| waiting[*t] == 0).collect();
+
+    let mut order = Vec::with_capacity(tasks.len());
+    while let Some(task) = ready.pop_first() {
+        order.push(task);
+        for &dependent in &tasks[task].dependents {
+            waiting[dependent] -= 1;
+            if waiting[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+
+    assert_eq!(
+        order.len(),
+        tasks.len(),
+        "Tasks depend on each other in a cycle"
+    );
+    order
+}
+
+impl CriticalPath {
+    pub fn new(tasks: &[Task], durations: &[u64]) -> Self {
+        let order = topological_order(tasks);
+
+        let mut earliest_start = vec![0; tasks.len()];
+        for &task in &order {
+            earliest_start[task] = tasks[task]
+                .dependencies
+                .iter()
+                .map(|d| earliest_start[*d] + durations[*d])
+                .max()
+                .unwrap_or(0);
+        }
+
+        let length = (0..tasks.len())
+            .map(|t| earliest_start[t] + durations[t])
+            .max()
+            .unwrap_or(0);
+
+        let mut latest_start = vec![0; tasks.len()];
+        for &task in order.iter().rev() {
+            let latest_finish = tasks[task]
+                .dependents
+                .iter()
+                .map(|d| latest_start[*d])
+                .min()
+                .unwrap_or(length);
+            latest_start[task] = latest_finish - durations[task];
+        }
+
+        let timings: Vec<_> = (0..tasks.len())
+            .map(|t| Timing {
+                duration: durations[t],
+                earliest_start: earliest_start[t],
+                latest_start: latest_start[t],
+            })
+            .collect();
+
+        // Walk forward from the first critical task with nothing before it, always to the first
+        // critical dependent that starts as soon as this task finishes.
+        let mut chain = vec![];
+        let mut next =
+            (0..tasks.len()).find(|t| timings[*t].slack() == 0 && timings[*t].earliest_start == 0);
+        while let Some(task) = next {
+            chain.push(task);
+            next = tasks[task].dependents.iter().cloned().find(|d| {
+                timings[*d].slack() == 0
+                    && timings[*d].earliest_start == timings[task].earliest_finish()
+            });
+        }
+
+        CriticalPath {
+            timings,
+            length,
+            chain,
+        }
+    }
+}
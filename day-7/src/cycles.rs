@@ -0,0 +1,111 @@
+//! Cycle detection by strongly connected components. Every cycle lies within a single component,
+//! so one cycle is reported per component rather than every cycle, of which there can be
+//! exponentially many.
+
+use std::collections::VecDeque;
+
+use crate::Task;
+
+/// The strongly connected components of the graph, following edges from each task to its
+/// dependents, by Tarjan's algorithm with an explicit stack.
+fn components(tasks: &[Task]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; tasks.len()];
+    let mut low_link = vec![0; tasks.len()];
+    let mut on_stack = vec![false; tasks.len()];
+    let mut stack = vec![];
+    let mut components = vec![];
+    let mut next_index = 0;
+
+    for root in 0..tasks.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // Each frame is a task and how many of its dependents have been followed so far.
+        let mut frames = vec![(root, 0)];
+        index[root] = next_index;
+        low_link[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&mut (task, ref mut followed)) = frames.last_mut() {
+            if let Some(&next) = tasks[task].dependents.get(*followed) {
+                *followed += 1;
+                if index[next] == UNVISITED {
+                    index[next] = next_index;
+                    low_link[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    frames.push((next, 0));
+                } else if on_stack[next] {
+                    low_link[task] = low_link[task].min(index[next]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low_link[parent] = low_link[parent].min(low_link[task]);
+            }
+
+            if low_link[task] == index[task] {
+                let mut component = vec![];
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == task {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// The shortest cycle from `start` back to itself that stays within `component`, which must be
+/// strongly connected and contain a cycle.
+fn shortest_cycle(tasks: &[Task], component: &[usize], start: usize) -> Vec<usize> {
+    let mut previous = vec![None; tasks.len()];
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(task) = queue.pop_front() {
+        for &next in &tasks[task].dependents {
+            if next == start {
+                let mut cycle = vec![task];
+                while let Some(p) = previous[*cycle.last().unwrap()] {
+                    cycle.push(p);
+                }
+                cycle.reverse();
+                return cycle;
+            }
+            if previous[next].is_none() && component.binary_search(&next).is_ok() {
+                previous[next] = Some(task);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    unreachable!("Component has no cycle through its first task")
+}
+
+/// One cycle for each group of tasks that depend on each other, as the tasks along it starting
+/// from the lowest id. A task that depends on itself is a cycle of one.
+pub fn cycles(tasks: &[Task]) -> Vec<Vec<usize>> {
+    let mut cycles: Vec<_> = components(tasks)
+        .into_iter()
+        .filter(|c| c.len() > 1 || tasks[c[0]].dependents.binary_search(&c[0]).is_ok())
+        .map(|c| shortest_cycle(tasks, &c, c[0]))
+        .collect();
+    cycles.sort_unstable();
+    cycles
+}
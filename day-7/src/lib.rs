@@ -1,31 +1,323 @@
+use std::collections::HashMap;
+use std::fmt;
+
+pub mod critical;
+mod cycles;
+pub mod policy;
+pub mod reduction;
+pub mod schedule;
+
 lazy_static::lazy_static! {
     static ref LINE_REGEX: regex::Regex =
-        regex::Regex::new(r"^Step ([A-Z]) must be finished before step ([A-Z]) can begin.$")
+        regex::Regex::new(r"^Step (\S+) must be finished before step (\S+) can begin\.$")
             .unwrap();
+    static ref ARROW_REGEX: regex::Regex = regex::Regex::new(r"^(\S+)\s*->\s*(\S+)$").unwrap();
+    static ref NAME_REGEX: regex::Regex = regex::Regex::new(r"^\S+$").unwrap();
+    static ref DURATION_REGEX: regex::Regex = regex::Regex::new(r"^(\S+)\s+(\d+)$").unwrap();
+}
+
+/// Task names, each interned to a small integer id.
+#[derive(Clone, Debug, Default)]
+pub struct TaskNames {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+}
+
+impl TaskNames {
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), id);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).cloned()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
 pub struct TaskDependency {
-    pub dependency: u8,
-    pub target: u8,
+    pub dependency: usize,
+    pub target: usize,
 }
 
 impl TaskDependency {
-    pub fn from_line(line: &str) -> Self {
-        let captures = LINE_REGEX.captures(line).expect("Input is malformed!");
+    /// Parses either `Step a must be finished before step b can begin.` or `a -> b`, interning
+    /// both names.
+    pub fn from_line(line: &str, names: &mut TaskNames) -> Self {
+        Self::parse(line, names).unwrap_or_else(|| panic!("Input is malformed: {}", line))
+    }
 
-        Self {
-            dependency: captures[1].as_bytes()[0] - b'A',
-            target: captures[2].as_bytes()[0] - b'A',
-        }
+    fn parse(line: &str, names: &mut TaskNames) -> Option<Self> {
+        let line = line.trim();
+        let captures = LINE_REGEX
+            .captures(line)
+            .or_else(|| ARROW_REGEX.captures(line))?;
+
+        Some(Self {
+            dependency: names.intern(&captures[1]),
+            target: names.intern(&captures[2]),
+        })
     }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct Task {
-    pub dependencies: Vec<u8>,
-    pub dependents: Vec<u8>,
+    pub dependencies: Vec<usize>,
+    pub dependents: Vec<usize>,
     pub incomplete: bool,
     pub in_progress: bool,
-    pub remaining_time: u8,
+    pub remaining_time: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GraphIssue {
+    /// Tasks that each depend on the one before, with the first depending on the last.
+    Cycle { path: Vec<usize> },
+    /// A dependency names a task that was never declared, first on the given 1-based line.
+    UnknownTask { task: usize, line: usize },
+}
+
+impl GraphIssue {
+    pub fn display<'a>(&'a self, graph: &'a TaskGraph) -> impl fmt::Display + 'a {
+        IssueDisplay { issue: self, graph }
+    }
+}
+
+struct IssueDisplay<'a> {
+    issue: &'a GraphIssue,
+    graph: &'a TaskGraph,
+}
+
+impl<'a> fmt::Display for IssueDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = &self.graph.names;
+        match self.issue {
+            GraphIssue::Cycle { path } => {
+                write!(f, "dependency cycle: ")?;
+                for task in path {
+                    write!(f, "{} -> ", names.name(*task))?;
+                }
+                write!(f, "{}", names.name(path[0]))
+            }
+            GraphIssue::UnknownTask { task, line } => {
+                write!(f, "line {}: unknown task {}", line, names.name(*task))
+            }
+        }
+    }
+}
+
+/// Every task in the input, with ids assigned in name order so that the lowest available id is
+/// always the task to pick next.
+pub struct TaskGraph {
+    pub names: TaskNames,
+    pub tasks: Vec<Task>,
+    unknown: Vec<GraphIssue>,
+}
+
+impl TaskGraph {
+    /// Parses one dependency per line. A line holding just a name declares a task, which need
+    /// not have any dependencies; once any task is declared, every task must be. Blank lines are
+    /// skipped.
+    pub fn parse(input: &str) -> Self {
+        let mut parsed_names = TaskNames::default();
+        let mut dependencies = vec![];
+        let mut declared = vec![];
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match TaskDependency::parse(line, &mut parsed_names) {
+                Some(dep) => dependencies.push((i + 1, dep)),
+                None if NAME_REGEX.is_match(line.trim()) => {
+                    declared.push(parsed_names.intern(line.trim()))
+                }
+                None => panic!("Input is malformed: {}", line),
+            }
+        }
+
+        let mut sorted: Vec<_> = parsed_names.names.iter().map(String::as_str).collect();
+        sorted.sort_unstable();
+
+        let mut names = TaskNames::default();
+        for name in sorted {
+            names.intern(name);
+        }
+        let renumber: Vec<_> = parsed_names
+            .names
+            .iter()
+            .map(|name| names.id(name).unwrap())
+            .collect();
+
+        let mut is_declared = vec![declared.is_empty(); names.len()];
+        for task in declared {
+            is_declared[renumber[task]] = true;
+        }
+
+        let mut tasks = vec![Task::default(); names.len()];
+        let mut unknown = vec![];
+        for (line, dep) in &dependencies {
+            let (dependency, target) = (renumber[dep.dependency], renumber[dep.target]);
+            tasks[target].dependencies.push(dependency);
+            tasks[dependency].dependents.push(target);
+
+            for &task in &[dependency, target] {
+                if !is_declared[task] {
+                    // Report each unknown task once, where it is first mentioned.
+                    is_declared[task] = true;
+                    unknown.push(GraphIssue::UnknownTask { task, line: *line });
+                }
+            }
+        }
+
+        for task in &mut tasks {
+            task.dependencies.sort();
+            task.dependencies.dedup();
+
+            task.dependents.sort();
+            task.dependents.dedup();
+        }
+
+        TaskGraph {
+            names,
+            tasks,
+            unknown,
+        }
+    }
+
+    /// Every unknown task, then one cycle for each group of tasks that depend on each other.
+    pub fn issues(&self) -> Vec<GraphIssue> {
+        let mut issues = self.unknown.clone();
+        issues.extend(
+            cycles::cycles(&self.tasks)
+                .into_iter()
+                .map(|path| GraphIssue::Cycle { path }),
+        );
+        issues
+    }
+
+    /// How long a task takes: `base` seconds, plus its position in the alphabet for
+    /// single-letter names.
+    pub fn duration(&self, task: usize, base: u64) -> u64 {
+        let name = self.names.name(task).as_bytes();
+        match name {
+            [letter @ b'A'..=b'Z'] => base + u64::from(letter - b'A') + 1,
+            _ => base,
+        }
+    }
+
+    /// Every task's duration, by task id; see `duration`.
+    pub fn durations(&self, base: u64) -> Vec<u64> {
+        (0..self.tasks.len())
+            .map(|task| self.duration(task, base))
+            .collect()
+    }
+
+    /// Replaces durations with those given as `name seconds` lines. Blank lines are skipped.
+    /// Returns a message for each line that is malformed or names an unknown task.
+    pub fn override_durations(
+        &self,
+        durations: &mut [u64],
+        input: &str,
+    ) -> Result<(), Vec<String>> {
+        let mut errors = vec![];
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let captures = match DURATION_REGEX.captures(line) {
+                Some(captures) => captures,
+                None => {
+                    errors.push(format!(
+                        "line {}: expected a task name and a duration",
+                        i + 1
+                    ));
+                    continue;
+                }
+            };
+            let duration = match captures[2].parse() {
+                Ok(duration) => duration,
+                Err(_) => {
+                    errors.push(format!("line {}: duration is too long", i + 1));
+                    continue;
+                }
+            };
+            match self.names.id(&captures[1]) {
+                Some(task) => durations[task] = duration,
+                None => errors.push(format!("line {}: unknown task {}", i + 1, &captures[1])),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Task names in the given order, run together if every name is a single character and
+    /// separated by spaces otherwise.
+    pub fn format_order(&self, order: &[usize]) -> String {
+        let names: Vec<_> = order.iter().map(|t| self.names.name(*t)).collect();
+        if names.iter().all(|name| name.chars().count() == 1) {
+            names.concat()
+        } else {
+            names.join(" ")
+        }
+    }
+}
+
+/// Parses a task graph for the command line tools. If it has any issues, they are all printed and
+/// the process exits.
+pub fn load_task_graph(input: &str) -> TaskGraph {
+    let graph = TaskGraph::parse(input);
+    let issues = graph.issues();
+
+    for issue in &issues {
+        eprintln!("error: {}", issue.display(&graph));
+    }
+
+    if !issues.is_empty() {
+        eprintln!("Task graph is invalid");
+        std::process::exit(1);
+    }
+
+    graph
+}
+
+/// Every task's duration for the command line tools; see `TaskGraph::durations`. Overrides are
+/// read from `overrides_file` if given, and if it has any errors they are all printed and the
+/// process exits.
+pub fn load_durations(graph: &TaskGraph, base: u64, overrides_file: Option<&str>) -> Vec<u64> {
+    let mut durations = graph.durations(base);
+    if let Some(overrides_file) = overrides_file {
+        let overrides =
+            std::fs::read_to_string(overrides_file).expect("Could not open durations file");
+        if let Err(errors) = graph.override_durations(&mut durations, &overrides) {
+            for error in errors {
+                eprintln!("error: {}: {}", overrides_file, error);
+            }
+            std::process::exit(1);
+        }
+    }
+    durations
 }
@@ -0,0 +1,91 @@
+//! Which ready task a free worker picks up next.
+
+use std::cmp::Reverse;
+
+use crate::critical::CriticalPath;
+use crate::Task;
+
+/// Ranks ready tasks: a worker picks the task with the highest priority, and the lowest id (so
+/// the first name alphabetically) among tasks with equal priority.
+pub trait SchedulingPolicy {
+    fn priority(&self, task: usize) -> u64;
+}
+
+/// Sorts tasks into the order `policy` would pick them in.
+pub fn rank(policy: &dyn SchedulingPolicy, tasks: &mut [usize]) {
+    tasks.sort_by_key(|task| (Reverse(policy.priority(*task)), *task));
+}
+
+/// The first task alphabetically, as in the puzzle.
+pub struct Alphabetical;
+
+impl SchedulingPolicy for Alphabetical {
+    fn priority(&self, _: usize) -> u64 {
+        0
+    }
+}
+
+/// The task that takes longest.
+pub struct LongestFirst {
+    pub durations: Vec<u64>,
+}
+
+impl SchedulingPolicy for LongestFirst {
+    fn priority(&self, task: usize) -> u64 {
+        self.durations[task]
+    }
+}
+
+/// The task that most other tasks directly depend on.
+pub struct MostDependentsFirst {
+    pub dependents: Vec<usize>,
+}
+
+impl SchedulingPolicy for MostDependentsFirst {
+    fn priority(&self, task: usize) -> u64 {
+        self.dependents[task] as u64
+    }
+}
+
+/// The task with the longest chain of work from its start to the end of the graph, which is the
+/// one with the least slack among tasks ready at the same time.
+pub struct CriticalPathFirst {
+    pub remaining: Vec<u64>,
+}
+
+impl SchedulingPolicy for CriticalPathFirst {
+    fn priority(&self, task: usize) -> u64 {
+        self.remaining[task]
+    }
+}
+
+pub const POLICY_NAMES: &[&str] = &[
+    "alphabetical",
+    "longest-first",
+    "most-dependents-first",
+    "critical-path-first",
+];
+
+/// The policy called `name`, one of `POLICY_NAMES`, for tasks taking `durations`.
+pub fn policy(name: &str, tasks: &[Task], durations: &[u64]) -> Box<dyn SchedulingPolicy> {
+    match name {
+        "alphabetical" => Box::new(Alphabetical),
+        "longest-first" => Box::new(LongestFirst {
+            durations: durations.to_vec(),
+        }),
+        "most-dependents-first" => Box::new(MostDependentsFirst {
+            dependents: tasks.iter().map(|t| t.dependents.len()).collect(),
+        }),
+        "critical-path-first" => {
+            let critical = CriticalPath::new(tasks, durations);
+            Box::new(CriticalPathFirst {
+                remaining: critical
+                    .timings
+                    .iter()
+                    .map(|t| critical.length - t.latest_start)
+                    .collect(),
+            })
+        }
+        _ => panic!("Unknown scheduling policy: {}", name),
+    }
+}
@@ -0,0 +1,43 @@
+//! Transitive reduction: dropping every dependency already implied by a longer chain of
+//! dependencies, which leaves the same ordering constraints with far fewer edges to draw.
+
+use crate::Task;
+
+const BITS: usize = 64;
+
+/// The set of tasks reachable from each task, following edges to dependents, as bitsets.
+fn reachable(tasks: &[Task], order: &[usize]) -> Vec<Vec<u64>> {
+    let words = tasks.len().div_ceil(BITS);
+    let mut reach = vec![vec![0u64; words]; tasks.len()];
+
+    // Dependents come later in the order, so their sets are complete before they are needed.
+    for &task in order.iter().rev() {
+        let mut set = vec![0u64; words];
+        for &dependent in &tasks[task].dependents {
+            set[dependent / BITS] |= 1 << (dependent % BITS);
+            for (word, other) in set.iter_mut().zip(reach[dependent].iter()) {
+                *word |= other;
+            }
+        }
+        reach[task] = set;
+    }
+    reach
+}
+
+/// Each task's dependents, keeping only those not also reachable through another dependent.
+/// `order` must put every task after its dependencies.
+pub fn reduced_dependents(tasks: &[Task], order: &[usize]) -> Vec<Vec<usize>> {
+    let reach = reachable(tasks, order);
+    let reaches = |from: usize, to: usize| reach[from][to / BITS] & (1 << (to % BITS)) != 0;
+
+    tasks
+        .iter()
+        .map(|task| {
+            task.dependents
+                .iter()
+                .cloned()
+                .filter(|&d| !task.dependents.iter().any(|&other| reaches(other, d)))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,193 @@
+use serde::Serialize;
+
+use crate::policy::{rank, SchedulingPolicy};
+use crate::Task;
+
+/// One task's run on a worker, from `start` until just before `end`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Slot {
+    pub task: usize,
+    pub worker: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Which worker ran each task and when, with slots in the order they started.
+#[derive(Clone, Debug, Serialize)]
+pub struct Schedule {
+    pub workers: usize,
+    pub slots: Vec<Slot>,
+}
+
+impl Schedule {
+    /// When the last task finishes.
+    pub fn total_time(&self) -> u64 {
+        self.slots.iter().map(|slot| slot.end).max().unwrap_or(0)
+    }
+
+    /// The task a worker is running during the second starting at `time`, if any.
+    pub fn running(&self, worker: usize, time: u64) -> Option<usize> {
+        self.slots
+            .iter()
+            .find(|slot| slot.worker == worker && slot.start <= time && time < slot.end)
+            .map(|slot| slot.task)
+    }
+}
+
+/// The order one worker completes every task in, always starting the available task `policy`
+/// ranks first. Tasks must not depend on each other in a cycle.
+pub fn order(tasks: &[Task], policy: &dyn SchedulingPolicy) -> Vec<usize> {
+    let mut tasks = tasks.to_vec();
+    for task in &mut tasks {
+        task.incomplete = true;
+    }
+
+    let mut num_completed = 0;
+    let mut completion_order = vec![];
+
+    let mut removals = vec![];
+    while num_completed != tasks.len() {
+        let mut ready: Vec<_> = tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.incomplete)
+            .filter(|(_, t)| t.dependencies.is_empty())
+            .map(|(i, _)| i)
+            .collect();
+        rank(policy, &mut ready);
+        let task_idx = *ready.first().expect("Exhausted task list too quickly");
+
+        removals.clear();
+        for dependent in &tasks[task_idx].dependents {
+            let remove_at = tasks[*dependent]
+                .dependencies
+                .binary_search(&task_idx)
+                .expect("Task dependency wasn't recorded");
+            removals.push((*dependent, remove_at));
+        }
+
+        for removal in &removals {
+            tasks[removal.0].dependencies.remove(removal.1);
+        }
+
+        tasks[task_idx].incomplete = false;
+
+        completion_order.push(task_idx);
+
+        num_completed += 1;
+    }
+
+    completion_order
+}
+
+/// Runs every task on `workers` workers, starting the available task `policy` ranks first
+/// whenever a worker is free. Tasks must not depend on each other in a cycle.
+pub fn schedule(
+    tasks: &[Task],
+    durations: &[u64],
+    workers: usize,
+    policy: &dyn SchedulingPolicy,
+) -> Schedule {
+    assert!(workers > 0, "Zero workers...");
+
+    let mut tasks = tasks.to_vec();
+    for (task, duration) in tasks.iter_mut().zip(durations) {
+        task.remaining_time = *duration;
+        task.incomplete = true;
+    }
+
+    let mut num_completed = 0;
+
+    let mut completion_time: u64 = 0;
+
+    let mut slots: Vec<Slot> = vec![];
+    let mut free_workers = vec![true; workers];
+
+    // Each in-progress task, with the index of its slot
+    let mut in_progress: Vec<(usize, usize)> = vec![];
+    let mut in_progress_removals = vec![];
+    let mut completed = vec![];
+    let mut removals = vec![];
+
+    while num_completed != tasks.len() {
+        in_progress_removals.clear();
+        completed.clear();
+        removals.clear();
+
+        let mut ready: Vec<_> = tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.in_progress)
+            .filter(|(_, t)| t.incomplete)
+            .filter(|(_, t)| t.dependencies.is_empty())
+            .map(|(i, _)| i)
+            .collect();
+        rank(policy, &mut ready);
+        ready.truncate(workers - in_progress.len());
+
+        // Hand new work to the lowest numbered free workers
+        for task_idx in ready {
+            let worker = free_workers.iter().position(|free| *free).unwrap();
+            free_workers[worker] = false;
+
+            tasks[task_idx].in_progress = true;
+            in_progress.push((task_idx, slots.len()));
+            slots.push(Slot {
+                task: task_idx,
+                worker,
+                start: completion_time,
+                end: completion_time,
+            });
+        }
+
+        assert!(!in_progress.is_empty());
+
+        // figure out which task is closest to being completed
+        let time_to_run = in_progress
+            .iter()
+            .map(|(task_idx, _)| tasks[*task_idx].remaining_time)
+            .min()
+            .unwrap();
+
+        completion_time += time_to_run;
+
+        // decrease remaining_time on all in-progress tasks and track completed
+        for (i, &(task_idx, slot)) in in_progress.iter().enumerate() {
+            tasks[task_idx].remaining_time -= time_to_run;
+
+            if tasks[task_idx].remaining_time == 0 {
+                tasks[task_idx].incomplete = false;
+                slots[slot].end = completion_time;
+                free_workers[slots[slot].worker] = true;
+                completed.push(task_idx);
+                in_progress_removals.push(i);
+            }
+        }
+
+        // Remove from in_progress in reverse
+        for to_remove in in_progress_removals.iter().rev() {
+            in_progress.remove(*to_remove);
+        }
+
+        num_completed += completed.len();
+
+        // Remove completed tasks
+        for task_idx in &completed {
+            for dependent in &tasks[*task_idx].dependents {
+                removals.push((*dependent, *task_idx));
+            }
+        }
+
+        // Search only once earlier removals are done, as two tasks finishing together can share
+        // a dependent
+        for &(dependent, task_idx) in &removals {
+            let dependencies = &mut tasks[dependent].dependencies;
+            let remove_at = dependencies
+                .binary_search(&task_idx)
+                .expect("Task dependency wasn't recorded");
+            dependencies.remove(remove_at);
+        }
+    }
+
+    Schedule { workers, slots }
+}